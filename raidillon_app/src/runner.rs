@@ -0,0 +1,214 @@
+use anyhow::Result;
+use raidillon_core::{profile_scope, profiler};
+use raidillon_ecs::{App, Events, Plugin, Stage};
+use raidillon_input::{CursorGrab, WinitEvent};
+use raidillon_render::{
+    init_render_window, ECSRenderer, GliumRenderer, PickRequest, PickResult, RenderPlugin, Screenshot, Viewport,
+    WindowResized,
+};
+use raidillon_ui::{Gui, GuiConfig, UiSystems, ViewportTexture};
+use winit::application::ApplicationHandler;
+use winit::event::{DeviceEvent, DeviceId, Event, WindowEvent};
+use winit::event_loop::{ActiveEventLoop, EventLoop};
+use winit::window::{CursorGrabMode, Window, WindowId};
+
+/// Opens a window and drives the [`App`] from the winit event loop.
+///
+/// Owns the usual boilerplate: display and [`ECSRenderer`] creation, Dear
+/// ImGui when `UiPlugin` is present, republishing raw events as
+/// [`WinitEvent`]s, grabbing the cursor as [`CursorGrab::requested`], resize
+/// handling, answering [`PickRequest`]s and [`Screenshot`]s and the
+/// AboutToWait/RedrawRequested frame cycle, which also delimits profiler
+/// frames. With a [`Viewport`] resource the world is drawn into a texture for
+/// the ImGui viewport panel instead of the whole window.
+pub struct WinitPlugin {
+    pub title: String,
+    pub size:  (u32, u32),
+}
+
+impl WinitPlugin {
+    pub fn new(title: &str, size: (u32, u32)) -> Self {
+        Self { title: title.to_owned(), size }
+    }
+}
+
+impl Plugin for WinitPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugin(RenderPlugin)
+            .add_event::<WinitEvent>();
+
+        let title = self.title.clone();
+        let size = self.size;
+        app.set_runner(move |app| run(app, &title, size));
+    }
+}
+
+fn run(mut app: App, title: &str, size: (u32, u32)) -> Result<()> {
+    let event_loop = EventLoop::builder().build()?;
+    let (window, display) = init_render_window(&event_loop, title, size)?;
+
+    let renderer = GliumRenderer::new(display.as_inner().clone())?;
+    let mut ecsr = ECSRenderer::new(renderer, std::mem::take(&mut app.world));
+    let ui = match app.resources.remove::<UiSystems>() {
+        Some(systems) => {
+            let config = app.resources.get::<GuiConfig>().map(|c| c.clone()).unwrap_or_default();
+            Some((Gui::new(&display, &window, &config)?, systems))
+        }
+        None => None,
+    };
+
+    app.run_startup(&mut ecsr.world);
+    ecsr.load_pending_models()?;
+
+    let (width, height) = window.inner_size().into();
+    app.resources
+        .fetch_mut::<Events<WindowResized>>()
+        .send(WindowResized { width, height });
+
+    let mut runner = WinitRunner { app, ecsr, ui, window };
+    event_loop.run_app(&mut runner)?;
+    runner.app.run_shutdown(&mut runner.ecsr.world);
+    Ok(())
+}
+
+struct WinitRunner {
+    app:    App,
+    ecsr:   ECSRenderer,
+    ui:     Option<(Gui, UiSystems)>,
+    window: Window,
+}
+
+impl WinitRunner {
+    fn handle_event(&mut self, event: &WinitEvent) {
+        if let Some((gui, _)) = &mut self.ui {
+            gui.handle_event(&self.window, event);
+        }
+        self.app.resources.fetch_mut::<Events<WinitEvent>>().send(event.clone());
+    }
+
+    /// Grab or release the cursor if systems changed the requested state.
+    fn follow_cursor_grab(&mut self) {
+        let Some((grabbed, requested)) = self.app.resources.get::<CursorGrab>().map(|c| (c.grabbed, c.requested))
+        else {
+            return;
+        };
+        if grabbed != requested {
+            self.set_cursor_grab(requested);
+        }
+    }
+
+    fn set_cursor_grab(&mut self, grab: bool) {
+        if grab {
+            if self
+                .window
+                .set_cursor_grab(CursorGrabMode::Confined)
+                .or_else(|_| self.window.set_cursor_grab(CursorGrabMode::Locked))
+                .is_err()
+            {
+                return;
+            }
+        } else {
+            let _ = self.window.set_cursor_grab(CursorGrabMode::None);
+        }
+        self.window.set_cursor_visible(!grab);
+        if let Some(mut cursor) = self.app.resources.get_mut::<CursorGrab>() {
+            cursor.grabbed = grab;
+        }
+    }
+
+    fn redraw(&mut self) {
+        profile_scope!("redraw");
+        self.app
+            .schedule
+            .run_stage(Stage::Render, &mut self.ecsr.world, &mut self.app.resources);
+
+        if let Err(err) = self.ecsr.load_pending_models() {
+            eprintln!("[app] {err:#}");
+        }
+
+        let viewport = self.app.resources.get::<Viewport>().map(|v| *v);
+        match (&mut self.ui, viewport) {
+            (Some((gui, ui_systems)), Some(viewport)) => {
+                let resources = &mut self.app.resources;
+                gui.render_world_in_viewport(&mut self.ecsr, &self.window, viewport.size.into(), |ui, ecsr, texture| {
+                    resources.insert(ViewportTexture(texture));
+                    ui_systems.run(ui, &mut ecsr.world, resources);
+                });
+            }
+            (Some((gui, ui_systems)), None) => {
+                let resources = &mut self.app.resources;
+                gui.render_world(&mut self.ecsr, &self.window, |ui, ecsr| {
+                    ui_systems.run(ui, &mut ecsr.world, resources);
+                });
+            }
+            (None, _) => self.ecsr.render(),
+        }
+
+        self.answer_picks();
+        self.take_screenshots();
+        profiler::end_frame();
+    }
+
+    /// Resolve the [`PickRequest`]s sent this frame against the rendered models.
+    fn answer_picks(&mut self) {
+        let requests: Vec<PickRequest> = self.app.resources.fetch_mut::<Events<PickRequest>>().drain().collect();
+        let mut results = self.app.resources.fetch_mut::<Events<PickResult>>();
+        for request in requests {
+            let hit = self.ecsr.pick(request.screen, request.size);
+            results.send(PickResult { request, hit });
+        }
+    }
+
+    fn take_screenshots(&mut self) {
+        let requests: Vec<Screenshot> = self.app.resources.fetch_mut::<Events<Screenshot>>().drain().collect();
+        for request in requests {
+            match self.ecsr.renderer.save_screenshot(&request.path) {
+                Ok(()) => eprintln!("[app] saved screenshot {:?}", request.path),
+                Err(err) => eprintln!("[app] {err:#}"),
+            }
+        }
+    }
+}
+
+impl ApplicationHandler for WinitRunner {
+    fn resumed(&mut self, _event_loop: &ActiveEventLoop) {}
+
+    fn window_event(&mut self, event_loop: &ActiveEventLoop, window_id: WindowId, event: WindowEvent) {
+        let event = Event::WindowEvent { window_id, event };
+        self.handle_event(&event);
+        let Event::WindowEvent { event, .. } = event else {
+            unreachable!()
+        };
+
+        match event {
+            WindowEvent::CloseRequested => event_loop.exit(),
+            WindowEvent::Resized(sz) => {
+                self.ecsr.renderer.display().resize(sz.into());
+                self.app
+                    .resources
+                    .fetch_mut::<Events<WindowResized>>()
+                    .send(WindowResized { width: sz.width, height: sz.height });
+            }
+            WindowEvent::RedrawRequested => self.redraw(),
+            _ => {}
+        }
+    }
+
+    fn device_event(&mut self, _event_loop: &ActiveEventLoop, device_id: DeviceId, event: DeviceEvent) {
+        self.handle_event(&Event::DeviceEvent { device_id, event });
+    }
+
+    fn about_to_wait(&mut self, _event_loop: &ActiveEventLoop) {
+        profiler::begin_frame();
+        {
+            profile_scope!("update");
+            self.app.update(&mut self.ecsr.world);
+        }
+        self.follow_cursor_grab();
+
+        if let Some((gui, _)) = &mut self.ui {
+            gui.prepare_frame(&self.window);
+        }
+        self.window.request_redraw();
+    }
+}
@@ -0,0 +1,64 @@
+//! Decoded sounds.
+
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+
+use crate::decode::{downmix, Decoder};
+
+/// A sound decoded into memory as interleaved `f32` samples, mono or stereo.
+#[derive(Clone, Debug)]
+pub struct AudioClip {
+    samples:     Vec<f32>,
+    channels:    u16,
+    sample_rate: u32,
+}
+
+impl AudioClip {
+    /// Clip from interleaved samples in -1..=1. More than two channels are
+    /// mixed down to stereo.
+    pub fn from_samples(samples: Vec<f32>, channels: u16, sample_rate: u32) -> Result<Self> {
+        if channels == 0 || sample_rate == 0 {
+            bail!("audio with {channels} channels at {sample_rate} Hz");
+        }
+        let samples = downmix(samples, channels);
+        Ok(Self { samples, channels: channels.min(2), sample_rate })
+    }
+
+    /// Decode a whole `.wav` or `.ogg` (Vorbis) file.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let decode = || {
+            let mut decoder = Decoder::open(path)?;
+            let mut samples = Vec::new();
+            while decoder.read(&mut samples, decoder.sample_rate() as usize)? {}
+            Self::from_samples(samples, decoder.channels(), decoder.sample_rate())
+        };
+        decode().with_context(|| format!("loading sound {path:?}"))
+    }
+
+    pub fn channels(&self) -> u16 {
+        self.channels
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// Number of sample frames, one sample per channel each.
+    pub fn frames(&self) -> usize {
+        self.samples.len() / self.channels as usize
+    }
+
+    pub fn duration(&self) -> f32 {
+        self.frames() as f32 / self.sample_rate as f32
+    }
+
+    /// Left and right sample of frame `i`; mono clips play on both sides.
+    pub fn frame(&self, i: usize) -> (f32, f32) {
+        match self.channels {
+            1 => (self.samples[i], self.samples[i]),
+            _ => (self.samples[i * 2], self.samples[i * 2 + 1]),
+        }
+    }
+}
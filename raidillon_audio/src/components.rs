@@ -0,0 +1,48 @@
+use serde::{Deserialize, Serialize};
+
+use crate::mixer::DEFAULT_BUS;
+
+/// Plays a sound file from the entity. Spatial sources are attenuated and
+/// panned by their `Transform` relative to the listener.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioSource {
+    /// Path of a `.wav` or `.ogg` file.
+    pub clip:         String,
+    /// Read the file while playing instead of loading it whole, for music
+    /// and other long sounds.
+    pub stream:       bool,
+    pub bus:          String,
+    pub volume:       f32,
+    pub pitch:        f32,
+    pub looping:      bool,
+    /// Plays while set; cleared when a non-looping sound ends.
+    pub playing:      bool,
+    pub spatial:      bool,
+    /// Distance up to which a spatial source plays at full volume.
+    pub min_distance: f32,
+    /// Distance past which a spatial source is silent.
+    pub max_distance: f32,
+}
+
+impl Default for AudioSource {
+    fn default() -> Self {
+        Self {
+            clip:         String::new(),
+            stream:       false,
+            bus:          DEFAULT_BUS.to_owned(),
+            volume:       1.0,
+            pitch:        1.0,
+            looping:      false,
+            playing:      false,
+            spatial:      true,
+            min_distance: 1.0,
+            max_distance: 50.0,
+        }
+    }
+}
+
+/// Hear the world from this entity: from its `Camera` if it has one, from
+/// its `Transform` otherwise. Without any listener the first camera is used.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct AudioListener;
@@ -0,0 +1,102 @@
+//! Incremental WAV and OGG decoding, shared by clips and streams.
+
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+use anyhow::{bail, Result};
+use hound::{SampleFormat, WavReader};
+use lewton::inside_ogg::OggStreamReader;
+
+enum Format {
+    Wav {
+        reader: WavReader<BufReader<File>>,
+        /// Scale of integer samples, `None` for float files.
+        scale:  Option<f32>,
+    },
+    Ogg(Box<OggStreamReader<BufReader<File>>>),
+}
+
+/// Reads a sound file a chunk at a time.
+pub(crate) struct Decoder {
+    format:      Format,
+    channels:    u16,
+    sample_rate: u32,
+}
+
+impl Decoder {
+    /// Open a `.wav` or `.ogg` (Vorbis) file.
+    pub fn open(path: &Path) -> Result<Self> {
+        let ext = path.extension().and_then(|e| e.to_str()).unwrap_or_default().to_ascii_lowercase();
+        let decoder = match ext.as_str() {
+            "wav" => {
+                let reader = WavReader::open(path)?;
+                let spec = reader.spec();
+                let scale = match spec.sample_format {
+                    SampleFormat::Float => None,
+                    SampleFormat::Int => Some(1.0 / (1_i64 << (spec.bits_per_sample - 1)) as f32),
+                };
+                Self { format: Format::Wav { reader, scale }, channels: spec.channels, sample_rate: spec.sample_rate }
+            }
+            "ogg" => {
+                let reader = OggStreamReader::new(BufReader::new(File::open(path)?))?;
+                let (channels, sample_rate) = (reader.ident_hdr.audio_channels, reader.ident_hdr.audio_sample_rate);
+                Self { format: Format::Ogg(Box::new(reader)), channels: channels.into(), sample_rate }
+            }
+            _ => bail!("unsupported audio format {ext:?}"),
+        };
+        if decoder.channels == 0 || decoder.sample_rate == 0 {
+            bail!("audio with {} channels at {} Hz", decoder.channels, decoder.sample_rate);
+        }
+        Ok(decoder)
+    }
+
+    pub fn channels(&self) -> u16 {
+        self.channels
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// Append at least `frames` more frames of interleaved samples to `out`
+    /// unless the file ends first. Returns false once it has ended.
+    pub fn read(&mut self, out: &mut Vec<f32>, frames: usize) -> Result<bool> {
+        let wanted = out.len() + frames * self.channels as usize;
+        match &mut self.format {
+            Format::Wav { reader, scale: None } => {
+                for sample in reader.samples::<f32>().take(wanted - out.len()) {
+                    out.push(sample?);
+                }
+            }
+            Format::Wav { reader, scale: Some(scale) } => {
+                for sample in reader.samples::<i32>().take(wanted - out.len()) {
+                    out.push(sample? as f32 * *scale);
+                }
+            }
+            Format::Ogg(reader) => {
+                while out.len() < wanted {
+                    let Some(packet) = reader.read_dec_packet_itl()? else { break };
+                    out.extend(packet.into_iter().map(|s| s as f32 / 32768.0));
+                }
+            }
+        }
+        Ok(out.len() >= wanted)
+    }
+}
+
+/// Mix interleaved samples with more than two channels down to stereo,
+/// averaging the first and second half of the channels into left and right.
+pub(crate) fn downmix(samples: Vec<f32>, channels: u16) -> Vec<f32> {
+    if channels <= 2 {
+        return samples;
+    }
+    samples
+        .chunks_exact(channels as usize)
+        .flat_map(|frame| {
+            let (left, right) = frame.split_at(frame.len() / 2);
+            let avg = |s: &[f32]| s.iter().sum::<f32>() / s.len() as f32;
+            [avg(left), avg(right)]
+        })
+        .collect()
+}
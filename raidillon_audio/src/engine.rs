@@ -0,0 +1,146 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use anyhow::Result;
+
+use crate::clip::AudioClip;
+use crate::mixer::{Mixer, PlayParams, VoiceId, MUSIC_BUS};
+use crate::output::{self, Output};
+use crate::spatial::Listener;
+use crate::stream::AudioStream;
+
+/// The audio output, its [`Mixer`] and the clips loaded so far.
+pub struct AudioEngine {
+    mixer:    Arc<Mutex<Mixer>>,
+    output:   Output,
+    clips:    HashMap<PathBuf, Arc<AudioClip>>,
+    listener: Listener,
+    music:    Option<VoiceId>,
+    /// Fractional frames the null output still owes the mixer.
+    owed:     f64,
+    scratch:  Vec<f32>,
+}
+
+impl AudioEngine {
+    /// Engine playing through the default output device. Falls back to a
+    /// silent null output when there is none or the `cpal` feature is off.
+    pub fn open() -> Self {
+        #[cfg(feature = "cpal")]
+        match output::open_device() {
+            Ok((mixer, output)) => return Self::with_output(mixer, output),
+            Err(err) => eprintln!("[audio] {err:#}, sound is disabled"),
+        }
+        #[cfg(not(feature = "cpal"))]
+        eprintln!("[audio] built without the `cpal` feature, sound is disabled");
+        Self::headless()
+    }
+
+    /// Engine with a null output: everything plays and finishes on time, but
+    /// nothing is heard.
+    pub fn headless() -> Self {
+        let (mixer, output) = output::null();
+        Self::with_output(mixer, output)
+    }
+
+    fn with_output(mixer: Arc<Mutex<Mixer>>, output: Output) -> Self {
+        Self {
+            mixer,
+            output,
+            clips: HashMap::new(),
+            listener: Listener::default(),
+            music: None,
+            owed: 0.0,
+            scratch: Vec::new(),
+        }
+    }
+
+    pub fn is_headless(&self) -> bool {
+        matches!(self.output, Output::Null)
+    }
+
+    /// The mixer, locked; keep the guard short-lived since the output
+    /// device waits on it.
+    pub fn mixer(&self) -> MutexGuard<'_, Mixer> {
+        output::lock(&self.mixer)
+    }
+
+    /// Decoded clip at `path`, loaded once and shared afterwards.
+    pub fn clip<P: AsRef<Path>>(&mut self, path: P) -> Result<Arc<AudioClip>> {
+        let path = path.as_ref();
+        if let Some(clip) = self.clips.get(path) {
+            return Ok(clip.clone());
+        }
+        let clip = Arc::new(AudioClip::load(path)?);
+        self.clips.insert(path.to_owned(), clip.clone());
+        Ok(clip)
+    }
+
+    pub fn play(&self, clip: Arc<AudioClip>, params: PlayParams) -> VoiceId {
+        self.mixer().play(clip, params)
+    }
+
+    /// Play a file streamed from disk instead of loaded whole.
+    pub fn play_stream<P: AsRef<Path>>(&self, path: P, params: PlayParams) -> Result<VoiceId> {
+        let stream = AudioStream::open(path)?;
+        Ok(self.mixer().play_stream(stream, params))
+    }
+
+    /// Stream `path` in a loop on the music bus, crossfading from the
+    /// current track over `fade` seconds.
+    pub fn play_music<P: AsRef<Path>>(&mut self, path: P, fade: f32) -> Result<VoiceId> {
+        let stream = AudioStream::open(path)?;
+        let previous = self.music.take();
+        let mut mixer = output::lock(&self.mixer);
+        if let Some(previous) = previous {
+            mixer.fade_out(previous, fade);
+        }
+        let params = PlayParams { looping: true, bus: MUSIC_BUS.to_owned(), ..Default::default() };
+        let track = mixer.play_stream(stream, params);
+        mixer.fade_in(track, fade);
+        self.music = Some(track);
+        Ok(track)
+    }
+
+    /// Fade the current track out over `fade` seconds.
+    pub fn stop_music(&mut self, fade: f32) {
+        if let Some(track) = self.music.take() {
+            self.mixer().fade_out(track, fade);
+        }
+    }
+
+    /// The current music track, unless it was stopped.
+    pub fn music(&self) -> Option<VoiceId> {
+        self.music
+    }
+
+    pub fn stop(&self, voice: VoiceId) {
+        self.mixer().stop(voice);
+    }
+
+    pub fn is_playing(&self, voice: VoiceId) -> bool {
+        self.mixer().is_playing(voice)
+    }
+
+    pub fn listener(&self) -> Listener {
+        self.listener
+    }
+
+    pub fn set_listener(&mut self, listener: Listener) {
+        self.listener = listener;
+    }
+
+    /// Mix `dt` seconds of audio into nothing when the output is null, so
+    /// sounds advance and end as they would on a device. A no-op otherwise.
+    pub fn advance(&mut self, dt: f32) {
+        if !self.is_headless() {
+            return;
+        }
+        let mut mixer = output::lock(&self.mixer);
+        self.owed += dt as f64 * mixer.sample_rate() as f64;
+        let frames = self.owed as usize;
+        self.owed -= frames as f64;
+        self.scratch.resize(frames * 2, 0.0);
+        mixer.mix(&mut self.scratch);
+    }
+}
@@ -0,0 +1,17 @@
+pub mod clip;
+pub mod components;
+mod decode;
+pub mod engine;
+pub mod mixer;
+pub mod output;
+pub mod plugin;
+pub mod spatial;
+pub mod stream;
+
+pub use clip::AudioClip;
+pub use components::{AudioListener, AudioSource};
+pub use engine::AudioEngine;
+pub use mixer::{BusSettings, Mixer, PlayParams, VoiceId, DEFAULT_BUS, MASTER_BUS, MUSIC_BUS, VOICE_BUS};
+pub use plugin::AudioPlugin;
+pub use spatial::Listener;
+pub use stream::AudioStream;
@@ -0,0 +1,342 @@
+//! Software mixer summing the playing voices through a tree of buses into a
+//! stereo buffer.
+
+use std::sync::Arc;
+
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::clip::AudioClip;
+use crate::stream::{AudioStream, FramePair};
+
+/// Root bus every other bus ends up in.
+pub const MASTER_BUS: &str = "master";
+pub const MUSIC_BUS: &str = "music";
+/// Bus voices play on unless told otherwise.
+pub const DEFAULT_BUS: &str = "sfx";
+pub const VOICE_BUS: &str = "voice";
+
+/// Identifies a playing sound. Ids are never reused.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct VoiceId(u64);
+
+/// How a voice plays; can be changed while it plays.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlayParams {
+    pub volume:  f32,
+    /// Playback speed, 2 is an octave up.
+    pub pitch:   f32,
+    /// -1 is fully left, 1 fully right.
+    pub pan:     f32,
+    pub looping: bool,
+    /// Bus to play on; unknown buses play straight into the master bus.
+    pub bus:     String,
+}
+
+impl Default for PlayParams {
+    fn default() -> Self {
+        Self { volume: 1.0, pitch: 1.0, pan: 0.0, looping: false, bus: DEFAULT_BUS.to_owned() }
+    }
+}
+
+/// Volume and effects of a bus, applied to everything mixed into it.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BusSettings {
+    pub volume:   f32,
+    pub muted:    bool,
+    /// Cutoff frequency of a low-pass filter in Hz, e.g. to muffle sound
+    /// under water. `None` for no filter.
+    pub low_pass: Option<f32>,
+}
+
+impl Default for BusSettings {
+    fn default() -> Self {
+        Self { volume: 1.0, muted: false, low_pass: None }
+    }
+}
+
+struct Bus {
+    name:     String,
+    parent:   Option<usize>,
+    settings: BusSettings,
+    /// Low-pass filter output of the last frame, left and right.
+    filtered: (f32, f32),
+    buffer:   Vec<f32>,
+}
+
+impl Bus {
+    fn new(name: &str, parent: Option<usize>) -> Self {
+        Self { name: name.to_owned(), parent, settings: BusSettings::default(), filtered: (0.0, 0.0), buffer: Vec::new() }
+    }
+
+    /// Apply the effects and volume to the mixed buffer.
+    fn process(&mut self, sample_rate: f32) {
+        if let Some(cutoff) = self.settings.low_pass {
+            // One-pole filter.
+            let a = 1.0 - (-std::f32::consts::TAU * cutoff.max(1.0) / sample_rate).exp();
+            for frame in self.buffer.chunks_exact_mut(2) {
+                self.filtered.0 += a * (frame[0] - self.filtered.0);
+                self.filtered.1 += a * (frame[1] - self.filtered.1);
+                frame[0] = self.filtered.0;
+                frame[1] = self.filtered.1;
+            }
+        }
+        let gain = if self.settings.muted { 0.0 } else { self.settings.volume };
+        self.buffer.iter_mut().for_each(|s| *s *= gain);
+    }
+}
+
+enum Sound {
+    Clip(Arc<AudioClip>),
+    Stream(Box<AudioStream>),
+}
+
+/// Gain change spread over many frames, so crossfades and stops don't click.
+struct Fade {
+    target: f32,
+    /// Gain change per frame.
+    step:   f32,
+    /// Stop the voice once the target is reached.
+    stop:   bool,
+}
+
+struct Voice {
+    id:     VoiceId,
+    sound:  Sound,
+    params: PlayParams,
+    /// Position in frames, fractional when resampling. For streams it is
+    /// relative to the stream's playing frame.
+    cursor: f64,
+    /// Fade gain, on top of the volume.
+    gain:   f32,
+    fade:   Option<Fade>,
+    done:   bool,
+}
+
+impl Voice {
+    fn sample_rate(&self) -> u32 {
+        match &self.sound {
+            Sound::Clip(clip) => clip.sample_rate(),
+            Sound::Stream(stream) => stream.sample_rate(),
+        }
+    }
+
+    /// The frames to interpolate between at the cursor, `None` at the end.
+    fn frames(&mut self) -> Option<FramePair> {
+        let looping = self.params.looping;
+        match &mut self.sound {
+            Sound::Clip(clip) => {
+                let frames = clip.frames();
+                if self.cursor >= frames as f64 {
+                    if !looping || frames == 0 {
+                        return None;
+                    }
+                    self.cursor %= frames as f64;
+                }
+                let i = self.cursor as usize;
+                let next = if i + 1 < frames { i + 1 } else if looping { 0 } else { i };
+                Some((clip.frame(i), clip.frame(next)))
+            }
+            Sound::Stream(stream) => {
+                let whole = self.cursor.floor();
+                stream.advance(whole as usize);
+                self.cursor -= whole;
+                stream.current(looping)
+            }
+        }
+    }
+
+    /// Add the next `out.len() / 2` frames to `out`.
+    fn render(&mut self, out: &mut [f32], sample_rate: f64) {
+        let (left, right) = pan_gains(self.params.pan);
+        let step = self.sample_rate() as f64 / sample_rate * self.params.pitch.max(0.0) as f64;
+        for frame in out.chunks_exact_mut(2) {
+            let Some(((l0, r0), (l1, r1))) = self.frames() else {
+                self.done = true;
+                return;
+            };
+            let t = self.cursor.fract() as f32;
+            let gain = self.params.volume * self.gain;
+            frame[0] += (l0 + (l1 - l0) * t) * gain * left;
+            frame[1] += (r0 + (r1 - r0) * t) * gain * right;
+            self.cursor += step;
+
+            if let Some(fade) = &self.fade {
+                let delta = fade.target - self.gain;
+                self.gain += delta.clamp(-fade.step, fade.step);
+                if self.gain == fade.target {
+                    if fade.stop {
+                        self.done = true;
+                        return;
+                    }
+                    self.fade = None;
+                }
+            }
+        }
+    }
+}
+
+/// Playing voices and the bus tree. The output backend calls
+/// [`Mixer::mix`] whenever it needs more samples.
+///
+/// Starts with a [`MASTER_BUS`] holding the [`MUSIC_BUS`], [`DEFAULT_BUS`]
+/// (sfx) and [`VOICE_BUS`].
+pub struct Mixer {
+    sample_rate: u32,
+    voices:      Vec<Voice>,
+    /// Parents come before their children.
+    buses:       Vec<Bus>,
+    next_id:     u64,
+}
+
+impl Mixer {
+    pub fn new(sample_rate: u32) -> Self {
+        let mut buses = vec![Bus::new(MASTER_BUS, None)];
+        buses.extend([MUSIC_BUS, DEFAULT_BUS, VOICE_BUS].map(|name| Bus::new(name, Some(0))));
+        Self { sample_rate, voices: Vec::new(), buses, next_id: 0 }
+    }
+
+    /// Output sample rate; sounds at other rates are resampled.
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    pub fn play(&mut self, clip: Arc<AudioClip>, params: PlayParams) -> VoiceId {
+        self.add_voice(Sound::Clip(clip), params)
+    }
+
+    pub fn play_stream(&mut self, stream: AudioStream, params: PlayParams) -> VoiceId {
+        self.add_voice(Sound::Stream(Box::new(stream)), params)
+    }
+
+    fn add_voice(&mut self, sound: Sound, params: PlayParams) -> VoiceId {
+        let id = VoiceId(self.next_id);
+        self.next_id += 1;
+        self.voices.push(Voice { id, sound, params, cursor: 0.0, gain: 1.0, fade: None, done: false });
+        id
+    }
+
+    pub fn stop(&mut self, id: VoiceId) {
+        self.voices.retain(|v| v.id != id);
+    }
+
+    pub fn stop_all(&mut self) {
+        self.voices.clear();
+    }
+
+    /// False once the voice was stopped or, unless looping, reached the end.
+    pub fn is_playing(&self, id: VoiceId) -> bool {
+        self.voices.iter().any(|v| v.id == id)
+    }
+
+    pub fn params_mut(&mut self, id: VoiceId) -> Option<&mut PlayParams> {
+        self.voice_mut(id).map(|v| &mut v.params)
+    }
+
+    pub fn voice_count(&self) -> usize {
+        self.voices.len()
+    }
+
+    fn voice_mut(&mut self, id: VoiceId) -> Option<&mut Voice> {
+        self.voices.iter_mut().find(|v| v.id == id)
+    }
+
+    /// Move the voice's fade gain to `target` over `seconds`, then stop it
+    /// if `stop` is set.
+    pub fn fade(&mut self, id: VoiceId, target: f32, seconds: f32, stop: bool) {
+        let frames = seconds * self.sample_rate as f32;
+        let Some(voice) = self.voice_mut(id) else { return };
+        if frames < 1.0 {
+            voice.gain = target;
+            voice.fade = None;
+            voice.done |= stop;
+            return;
+        }
+        let step = (target - voice.gain).abs() / frames;
+        voice.fade = Some(Fade { target, step: step.max(f32::EPSILON), stop });
+    }
+
+    /// Restart the voice silent and fade it up to full over `seconds`.
+    pub fn fade_in(&mut self, id: VoiceId, seconds: f32) {
+        if let Some(voice) = self.voice_mut(id) {
+            voice.gain = 0.0;
+        }
+        self.fade(id, 1.0, seconds, false);
+    }
+
+    /// Fade the voice out over `seconds` and stop it.
+    pub fn fade_out(&mut self, id: VoiceId, seconds: f32) {
+        self.fade(id, 0.0, seconds, true);
+    }
+
+    /// Add a bus mixed into `parent`.
+    pub fn add_bus(&mut self, name: &str, parent: &str) -> Result<()> {
+        if self.bus_index(name).is_some() {
+            bail!("audio bus {name:?} already exists");
+        }
+        let Some(parent) = self.bus_index(parent) else {
+            bail!("unknown audio bus {parent:?}");
+        };
+        self.buses.push(Bus::new(name, Some(parent)));
+        Ok(())
+    }
+
+    /// Bus names, every parent before its children.
+    pub fn buses(&self) -> impl Iterator<Item = &str> {
+        self.buses.iter().map(|b| b.name.as_str())
+    }
+
+    /// Bus `name` is mixed into, `None` for the master bus.
+    pub fn bus_parent(&self, name: &str) -> Option<&str> {
+        let parent = self.buses[self.bus_index(name)?].parent?;
+        Some(&self.buses[parent].name)
+    }
+
+    pub fn bus(&self, name: &str) -> Option<&BusSettings> {
+        self.bus_index(name).map(|i| &self.buses[i].settings)
+    }
+
+    pub fn bus_mut(&mut self, name: &str) -> Option<&mut BusSettings> {
+        self.bus_index(name).map(|i| &mut self.buses[i].settings)
+    }
+
+    fn bus_index(&self, name: &str) -> Option<usize> {
+        self.buses.iter().position(|b| b.name == name)
+    }
+
+    /// Fill `out`, interleaved stereo, with the next `out.len() / 2` frames
+    /// and advance every voice past them.
+    pub fn mix(&mut self, out: &mut [f32]) {
+        for bus in &mut self.buses {
+            bus.buffer.clear();
+            bus.buffer.resize(out.len(), 0.0);
+        }
+        let rate = self.sample_rate as f64;
+        for voice in &mut self.voices {
+            let bus = self.buses.iter().position(|b| b.name == voice.params.bus).unwrap_or(0);
+            voice.render(&mut self.buses[bus].buffer, rate);
+        }
+        self.voices.retain(|v| !v.done);
+
+        out.fill(0.0);
+        for i in (0..self.buses.len()).rev() {
+            self.buses[i].process(self.sample_rate as f32);
+            let buffer = std::mem::take(&mut self.buses[i].buffer);
+            let target = match self.buses[i].parent {
+                Some(parent) => &mut self.buses[parent].buffer[..],
+                None => &mut *out,
+            };
+            target.iter_mut().zip(&buffer).for_each(|(t, s)| *t += s);
+            self.buses[i].buffer = buffer;
+        }
+        out.iter_mut().for_each(|s| *s = s.clamp(-1.0, 1.0));
+    }
+}
+
+/// Left and right gain for `pan`: the far side fades out, the near side
+/// stays at full volume.
+fn pan_gains(pan: f32) -> (f32, f32) {
+    let pan = pan.clamp(-1.0, 1.0);
+    ((1.0 - pan).min(1.0), (1.0 + pan).min(1.0))
+}
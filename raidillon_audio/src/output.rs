@@ -0,0 +1,81 @@
+//! Where mixed audio goes.
+
+use std::sync::{Arc, Mutex};
+
+use crate::mixer::Mixer;
+
+/// Sample rate of the null output.
+pub const NULL_SAMPLE_RATE: u32 = 48_000;
+
+pub(crate) enum Output {
+    /// Mixes into nothing, for headless runs.
+    Null,
+    /// Plays until the stream is dropped.
+    #[cfg(feature = "cpal")]
+    Device(#[allow(dead_code)] cpal::Stream),
+}
+
+/// Lock the mixer even if an audio callback panicked while holding it.
+pub(crate) fn lock(mixer: &Mutex<Mixer>) -> std::sync::MutexGuard<'_, Mixer> {
+    mixer.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Start playing a new mixer through the default output device.
+#[cfg(feature = "cpal")]
+pub(crate) fn open_device() -> anyhow::Result<(Arc<Mutex<Mixer>>, Output)> {
+    use anyhow::{bail, Context};
+    use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+    use cpal::SampleFormat;
+
+    let device = cpal::default_host().default_output_device().context("no audio output device")?;
+    let supported = device.default_output_config()?;
+    let config = supported.config();
+    let mixer = Arc::new(Mutex::new(Mixer::new(config.sample_rate.0)));
+    let stream = match supported.sample_format() {
+        SampleFormat::F32 => build_stream::<f32>(&device, &config, mixer.clone())?,
+        SampleFormat::I16 => build_stream::<i16>(&device, &config, mixer.clone())?,
+        SampleFormat::U16 => build_stream::<u16>(&device, &config, mixer.clone())?,
+        format => bail!("unsupported output sample format {format:?}"),
+    };
+    stream.play()?;
+    Ok((mixer, Output::Device(stream)))
+}
+
+#[cfg(feature = "cpal")]
+fn build_stream<T>(
+    device: &cpal::Device,
+    config: &cpal::StreamConfig,
+    mixer: Arc<Mutex<Mixer>>,
+) -> anyhow::Result<cpal::Stream>
+where
+    T: cpal::SizedSample + cpal::FromSample<f32>,
+{
+    use cpal::traits::DeviceTrait;
+
+    let channels = config.channels as usize;
+    let mut stereo = Vec::new();
+    let stream = device.build_output_stream(
+        config,
+        move |data: &mut [T], _: &cpal::OutputCallbackInfo| {
+            stereo.resize(data.len() / channels * 2, 0.0);
+            lock(&mixer).mix(&mut stereo);
+            for (out, frame) in data.chunks_exact_mut(channels).zip(stereo.chunks_exact(2)) {
+                if channels == 1 {
+                    out[0] = T::from_sample((frame[0] + frame[1]) * 0.5);
+                    continue;
+                }
+                out[0] = T::from_sample(frame[0]);
+                out[1] = T::from_sample(frame[1]);
+                out[2..].iter_mut().for_each(|s| *s = T::from_sample(0.0));
+            }
+        },
+        |err| eprintln!("[audio] {err}"),
+        None,
+    )?;
+    Ok(stream)
+}
+
+/// A mixer nobody listens to.
+pub(crate) fn null() -> (Arc<Mutex<Mixer>>, Output) {
+    (Arc::new(Mutex::new(Mixer::new(NULL_SAMPLE_RATE))), Output::Null)
+}
@@ -0,0 +1,137 @@
+use std::collections::{HashMap, HashSet};
+
+use hecs::{Entity, World};
+use raidillon_core::Time;
+use raidillon_ecs::{App, Camera, Plugin, Resources, Stage, Transform};
+
+use crate::components::{AudioListener, AudioSource};
+use crate::engine::AudioEngine;
+use crate::mixer::{PlayParams, VoiceId};
+use crate::spatial::Listener;
+
+/// Inserts the [`AudioEngine`], plays every [`AudioSource`] from the
+/// listener's point of view and makes the audio components part of saved
+/// scenes and reflection.
+#[derive(Default)]
+pub struct AudioPlugin {
+    /// Use the null output even when a device is available.
+    pub headless: bool,
+}
+
+impl AudioPlugin {
+    pub fn headless() -> Self {
+        Self { headless: true }
+    }
+}
+
+impl Plugin for AudioPlugin {
+    fn build(&self, app: &mut App) {
+        let engine = if self.headless { AudioEngine::headless() } else { AudioEngine::open() };
+        app.insert_resource(engine)
+            .register_scene_component::<AudioSource>("AudioSource")
+            .register_scene_component::<AudioListener>("AudioListener")
+            .reflect::<AudioSource>("AudioSource", |s| {
+                s.insertable()
+                    .field("clip", |s| &mut s.clip)
+                    .field("stream", |s| &mut s.stream)
+                    .field("bus", |s| &mut s.bus)
+                    .field_range("volume", 0.0..=2.0, |s| &mut s.volume)
+                    .field_range("pitch", 0.1..=4.0, |s| &mut s.pitch)
+                    .field("looping", |s| &mut s.looping)
+                    .field("playing", |s| &mut s.playing)
+                    .field("spatial", |s| &mut s.spatial)
+                    .field_range("min_distance", 0.01..=100.0, |s| &mut s.min_distance)
+                    .field_range("max_distance", 0.01..=1000.0, |s| &mut s.max_distance);
+            })
+            .reflect::<AudioListener>("AudioListener", |l| {
+                l.insertable();
+            })
+            .add_system(Stage::PostUpdate, "audio", update_audio());
+    }
+}
+
+/// Start, stop and spatialize the voices of the [`AudioSource`]s.
+fn update_audio() -> impl FnMut(&mut World, &mut Resources) {
+    let mut voices = HashMap::<Entity, (VoiceId, String)>::new();
+    move |world, res| {
+        let mut engine = res.fetch_mut::<AudioEngine>();
+        if let Some(listener) = find_listener(world) {
+            engine.set_listener(listener);
+        }
+        let listener = engine.listener();
+
+        let mut alive = HashSet::new();
+        for (entity, (source, transform)) in world.query_mut::<(&mut AudioSource, Option<&Transform>)>() {
+            alive.insert(entity);
+            if let Some((voice, clip)) = voices.get(&entity) {
+                if !source.playing || *clip != source.clip {
+                    engine.stop(*voice);
+                    voices.remove(&entity);
+                } else if !engine.is_playing(*voice) {
+                    source.playing = false;
+                    voices.remove(&entity);
+                }
+            }
+
+            let params = play_params(source, transform, &listener);
+            match voices.get(&entity) {
+                Some((voice, _)) => {
+                    if let Some(playing) = engine.mixer().params_mut(*voice) {
+                        *playing = params;
+                    }
+                }
+                None if source.playing => {
+                    let voice = if source.stream {
+                        engine.play_stream(&source.clip, params)
+                    } else {
+                        engine.clip(&source.clip).map(|clip| engine.play(clip, params))
+                    };
+                    match voice {
+                        Ok(voice) => {
+                            voices.insert(entity, (voice, source.clip.clone()));
+                        }
+                        Err(err) => {
+                            eprintln!("[audio] {err:#}");
+                            source.playing = false;
+                        }
+                    }
+                }
+                None => {}
+            }
+        }
+        voices.retain(|entity, (voice, _)| {
+            let keep = alive.contains(entity);
+            if !keep {
+                engine.stop(*voice);
+            }
+            keep
+        });
+
+        engine.advance(res.fetch::<Time>().delta_seconds());
+    }
+}
+
+/// The [`AudioListener`] entity's point of view, or the first camera's.
+fn find_listener(world: &World) -> Option<Listener> {
+    let mut listeners = world.query::<(&AudioListener, Option<&Camera>, Option<&Transform>)>();
+    let listener = listeners.iter().find_map(|(_, (_, camera, transform))| {
+        camera.map(Listener::from_camera).or_else(|| transform.map(Listener::from_transform))
+    });
+    listener.or_else(|| world.query::<&Camera>().iter().next().map(|(_, camera)| Listener::from_camera(camera)))
+}
+
+fn play_params(source: &AudioSource, transform: Option<&Transform>, listener: &Listener) -> PlayParams {
+    let (gain, pan) = match transform {
+        Some(transform) if source.spatial => {
+            listener.spatialize(transform.translation, source.min_distance, source.max_distance)
+        }
+        _ => (1.0, 0.0),
+    };
+    PlayParams {
+        volume:  source.volume * gain,
+        pitch:   source.pitch,
+        pan,
+        looping: source.looping,
+        bus:     source.bus.clone(),
+    }
+}
@@ -0,0 +1,45 @@
+//! Where sounds are heard from.
+
+use glam::Vec3;
+use raidillon_ecs::{Camera, Transform};
+
+/// Position and orientation sounds are heard from.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Listener {
+    pub position: Vec3,
+    pub forward:  Vec3,
+    pub up:       Vec3,
+}
+
+impl Default for Listener {
+    fn default() -> Self {
+        Self { position: Vec3::ZERO, forward: Vec3::NEG_Z, up: Vec3::Y }
+    }
+}
+
+impl Listener {
+    pub fn from_camera(camera: &Camera) -> Self {
+        Self { position: camera.eye, forward: camera.center - camera.eye, up: camera.up }
+    }
+
+    pub fn from_transform(transform: &Transform) -> Self {
+        Self {
+            position: transform.translation,
+            forward:  transform.rotation * Vec3::NEG_Z,
+            up:       transform.rotation * Vec3::Y,
+        }
+    }
+
+    /// Gain and pan of a sound at `position`. Gain is 1 up to `min_distance`,
+    /// falls off as `min_distance / distance` and is 0 past `max_distance`.
+    pub fn spatialize(&self, position: Vec3, min_distance: f32, max_distance: f32) -> (f32, f32) {
+        let offset = position - self.position;
+        let distance = offset.length();
+        let min_distance = min_distance.max(1e-3);
+        let gain = if distance > max_distance { 0.0 } else { min_distance / distance.max(min_distance) };
+
+        let right = self.forward.cross(self.up).normalize_or_zero();
+        let pan = if distance > 1e-4 { offset.dot(right) / distance } else { 0.0 };
+        (gain, pan)
+    }
+}
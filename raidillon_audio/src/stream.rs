@@ -0,0 +1,146 @@
+//! Sounds played while they are read from disk.
+//!
+//! Each stream decodes on its own thread, a few chunks ahead of playback, so
+//! the output callback only takes frames that are already decoded. The
+//! decoder starts over at the end of the file and marks where it ended;
+//! whether a voice loops is only decided when it gets there.
+
+use std::collections::VecDeque;
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver, SyncSender, TryRecvError};
+use std::thread;
+
+use anyhow::{bail, Context, Result};
+
+use crate::decode::{downmix, Decoder};
+
+/// A frame and the one after it, left and right, to interpolate between.
+pub(crate) type FramePair = ((f32, f32), (f32, f32));
+
+/// Frames decoded at a time.
+const CHUNK_FRAMES: usize = 4096;
+/// Chunks decoded ahead of playback.
+const CHUNKS_AHEAD: usize = 4;
+
+/// Decoded frames, `last` when they end the file.
+struct Chunk {
+    frames: Vec<(f32, f32)>,
+    last:   bool,
+}
+
+/// A sound file decoded a little ahead of playback, for music and other long
+/// sounds that should not sit in memory whole.
+pub struct AudioStream {
+    sample_rate: u32,
+    chunks:      Receiver<Chunk>,
+    /// Decoded frames not played yet; the front one is playing.
+    frames:      VecDeque<(f32, f32)>,
+    /// Frames in `frames` before each end of the file, oldest first.
+    ends:        VecDeque<usize>,
+    /// The decoder thread stopped; no more chunks will come.
+    finished:    bool,
+}
+
+impl AudioStream {
+    /// Open `path` and start decoding it in the background. Errors after
+    /// opening are logged by the decoder thread and end the stream.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let decoder = Decoder::open(path).with_context(|| format!("opening stream {path:?}"))?;
+        let sample_rate = decoder.sample_rate();
+        let (sender, chunks) = mpsc::sync_channel(CHUNKS_AHEAD);
+        let path = path.to_owned();
+        thread::Builder::new()
+            .name("audio stream".to_owned())
+            .spawn(move || {
+                if let Err(err) = decode(&path, decoder, sender) {
+                    eprintln!("[audio] {err:#}");
+                }
+            })
+            .context("starting stream decoder")?;
+        Ok(Self {
+            sample_rate,
+            chunks,
+            frames: VecDeque::new(),
+            ends: VecDeque::new(),
+            finished: false,
+        })
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// The playing frame and the one after it. At the end of the file it
+    /// carries on from the start when `looping` and returns `None`
+    /// otherwise. Plays silence while the decoder is behind.
+    pub(crate) fn current(&mut self, looping: bool) -> Option<FramePair> {
+        if self.frames.len() < 2 {
+            self.receive();
+        }
+        while self.ends.front() == Some(&0) {
+            if !looping {
+                return None;
+            }
+            self.ends.pop_front();
+        }
+        let at_end = !looping && self.ends.front() == Some(&1);
+        match (self.frames.front(), self.frames.get(1)) {
+            (Some(&a), Some(&b)) if !at_end => Some((a, b)),
+            (Some(&a), _) => Some((a, a)),
+            (None, _) if self.finished => None,
+            (None, _) => Some(((0.0, 0.0), (0.0, 0.0))),
+        }
+    }
+
+    /// Drop the next `frames` frames.
+    pub(crate) fn advance(&mut self, frames: usize) {
+        let frames = frames.min(self.frames.len());
+        self.frames.drain(..frames);
+        self.ends.iter_mut().for_each(|end| *end -= frames.min(*end));
+    }
+
+    /// Take the chunks decoded so far, without waiting for more.
+    fn receive(&mut self) {
+        while !self.finished {
+            match self.chunks.try_recv() {
+                Ok(chunk) => {
+                    self.frames.extend(chunk.frames);
+                    if chunk.last {
+                        self.ends.push_back(self.frames.len());
+                    }
+                }
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => self.finished = true,
+            }
+        }
+    }
+}
+
+/// Decode `path` over and over into `sender` until the stream is dropped.
+fn decode(path: &Path, mut decoder: Decoder, sender: SyncSender<Chunk>) -> Result<()> {
+    let mut samples = Vec::new();
+    let mut decoded = 0;
+    loop {
+        samples.clear();
+        let more = decoder.read(&mut samples, CHUNK_FRAMES)?;
+        let channels = decoder.channels();
+        let stereo = downmix(std::mem::take(&mut samples), channels);
+        let frames: Vec<_> = match channels {
+            1 => stereo.iter().map(|&s| (s, s)).collect(),
+            _ => stereo.chunks_exact(2).map(|f| (f[0], f[1])).collect(),
+        };
+        samples = stereo;
+        decoded += frames.len();
+        if sender.send(Chunk { frames, last: !more }).is_err() {
+            return Ok(());
+        }
+        if !more {
+            if decoded == 0 {
+                bail!("stream {path:?} is empty");
+            }
+            decoder = Decoder::open(path).with_context(|| format!("reopening stream {path:?}"))?;
+            decoded = 0;
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use std::sync::Arc;
+
+use raidillon_audio::output::NULL_SAMPLE_RATE;
+use raidillon_audio::{AudioClip, AudioEngine, Mixer, PlayParams, DEFAULT_BUS, MASTER_BUS, MUSIC_BUS};
+
+/// Mono clip holding `value` for `frames` frames.
+fn constant(value: f32, frames: usize) -> Arc<AudioClip> {
+    Arc::new(AudioClip::from_samples(vec![value; frames], 1, NULL_SAMPLE_RATE).unwrap())
+}
+
+fn looping(bus: &str) -> PlayParams {
+    PlayParams { looping: true, bus: bus.to_owned(), ..Default::default() }
+}
+
+/// Mix `frames` frames and return the left and right sample of the last one.
+fn mix(mixer: &mut Mixer, frames: usize) -> (f32, f32) {
+    let mut out = vec![0.0; frames * 2];
+    mixer.mix(&mut out);
+    (out[frames * 2 - 2], out[frames * 2 - 1])
+}
+
+#[test]
+fn bus_volume_scales_its_voices() {
+    let mut mixer = Mixer::new(NULL_SAMPLE_RATE);
+    mixer.play(constant(0.5, 16), looping(DEFAULT_BUS));
+    mixer.play(constant(0.25, 16), looping(MUSIC_BUS));
+    assert_eq!(mix(&mut mixer, 8), (0.75, 0.75));
+
+    mixer.bus_mut(DEFAULT_BUS).unwrap().volume = 0.5;
+    assert_eq!(mix(&mut mixer, 8), (0.5, 0.5));
+
+    // Master applies on top of the bus volume.
+    mixer.bus_mut(MASTER_BUS).unwrap().volume = 0.5;
+    assert_eq!(mix(&mut mixer, 8), (0.25, 0.25));
+}
+
+#[test]
+fn muted_bus_silences_its_children() {
+    let mut mixer = Mixer::new(NULL_SAMPLE_RATE);
+    mixer.add_bus("footsteps", DEFAULT_BUS).unwrap();
+    let voice = mixer.play(constant(0.5, 16), looping("footsteps"));
+    mixer.play(constant(0.25, 16), looping(MUSIC_BUS));
+
+    mixer.bus_mut(DEFAULT_BUS).unwrap().muted = true;
+    assert_eq!(mix(&mut mixer, 8), (0.25, 0.25));
+    // Muted voices keep playing.
+    assert!(mixer.is_playing(voice));
+
+    mixer.bus_mut(DEFAULT_BUS).unwrap().muted = false;
+    assert_eq!(mix(&mut mixer, 8), (0.75, 0.75));
+}
+
+#[test]
+fn pan_moves_voice_to_one_side() {
+    let mut mixer = Mixer::new(NULL_SAMPLE_RATE);
+    let voice = mixer.play(constant(0.5, 16), PlayParams { pan: 1.0, ..looping(DEFAULT_BUS) });
+    assert_eq!(mix(&mut mixer, 8), (0.0, 0.5));
+
+    mixer.params_mut(voice).unwrap().pan = -1.0;
+    assert_eq!(mix(&mut mixer, 8), (0.5, 0.0));
+}
+
+#[test]
+fn one_shot_voice_is_removed_at_its_end() {
+    let mut mixer = Mixer::new(NULL_SAMPLE_RATE);
+    let voice = mixer.play(constant(0.5, 100), PlayParams::default());
+
+    mix(&mut mixer, 64);
+    assert!(mixer.is_playing(voice));
+    // Silence after the last frame.
+    assert_eq!(mix(&mut mixer, 64), (0.0, 0.0));
+    assert!(!mixer.is_playing(voice));
+    assert_eq!(mixer.voice_count(), 0);
+}
+
+#[test]
+fn headless_engine_finishes_sounds_on_time() {
+    let mut engine = AudioEngine::headless();
+    assert!(engine.is_headless());
+    // A tenth of a second.
+    let voice = engine.play(constant(0.5, NULL_SAMPLE_RATE as usize / 10), PlayParams::default());
+
+    engine.advance(0.05);
+    assert!(engine.is_playing(voice));
+    engine.advance(0.06);
+    assert!(!engine.is_playing(voice));
+}
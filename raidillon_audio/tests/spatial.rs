@@ -0,0 +1,94 @@
+use std::path::PathBuf;
+
+use glam::Vec3;
+use hecs::Entity;
+use raidillon_audio::output::NULL_SAMPLE_RATE;
+use raidillon_audio::{AudioEngine, AudioListener, AudioPlugin, AudioSource};
+use raidillon_ecs::{App, Transform};
+
+/// Mono `.wav` of constant 0.5, written once per test.
+fn constant_wav(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("raidillon_audio_{}_{name}.wav", std::process::id()));
+    let spec = hound::WavSpec {
+        channels:        1,
+        sample_rate:     NULL_SAMPLE_RATE,
+        bits_per_sample: 16,
+        sample_format:   hound::SampleFormat::Int,
+    };
+    let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+    for _ in 0..NULL_SAMPLE_RATE {
+        writer.write_sample(i16::MAX / 2 + 1).unwrap();
+    }
+    writer.finalize().unwrap();
+    path
+}
+
+fn at(translation: Vec3) -> Transform {
+    Transform { translation, ..Default::default() }
+}
+
+/// App with a listener at the origin looking down -Z and a looping source.
+fn app(clip: PathBuf, position: Vec3) -> (App, Entity) {
+    let mut app = App::new();
+    app.add_plugin(AudioPlugin::headless());
+    app.world.spawn((AudioListener, Transform::default()));
+    let source = AudioSource {
+        clip:         clip.to_string_lossy().into_owned(),
+        looping:      true,
+        playing:      true,
+        min_distance: 1.0,
+        ..Default::default()
+    };
+    let entity = app.world.spawn((source, at(position)));
+    (app, entity)
+}
+
+fn update(app: &mut App) {
+    let mut world = std::mem::take(&mut app.world);
+    app.update(&mut world);
+    app.world = world;
+}
+
+/// Left and right sample of the next frame the engine would play.
+fn next_frame(app: &App) -> (f32, f32) {
+    let mut out = [0.0; 2];
+    app.resources.fetch::<AudioEngine>().mixer().mix(&mut out);
+    (out[0], out[1])
+}
+
+fn assert_close(actual: (f32, f32), expected: (f32, f32)) {
+    let close = (actual.0 - expected.0).abs() < 1e-3 && (actual.1 - expected.1).abs() < 1e-3;
+    assert!(close, "got {actual:?}, expected {expected:?}");
+}
+
+#[test]
+fn source_is_attenuated_by_distance() {
+    let clip = constant_wav("distance");
+    let (mut app, source) = app(clip.clone(), Vec3::new(0.0, 0.0, -0.5));
+    update(&mut app);
+    // Within min_distance and straight ahead: full volume, centered.
+    assert_close(next_frame(&app), (0.5, 0.5));
+
+    app.world.get::<&mut Transform>(source).unwrap().translation = Vec3::new(0.0, 0.0, -4.0);
+    update(&mut app);
+    assert_close(next_frame(&app), (0.125, 0.125));
+
+    // Past max_distance.
+    app.world.get::<&mut Transform>(source).unwrap().translation = Vec3::new(0.0, 0.0, -60.0);
+    update(&mut app);
+    assert_close(next_frame(&app), (0.0, 0.0));
+    let _ = std::fs::remove_file(clip);
+}
+
+#[test]
+fn source_is_panned_by_side() {
+    let clip = constant_wav("pan");
+    let (mut app, source) = app(clip.clone(), Vec3::new(2.0, 0.0, 0.0));
+    update(&mut app);
+    assert_close(next_frame(&app), (0.0, 0.25));
+
+    app.world.get::<&mut Transform>(source).unwrap().translation = Vec3::new(-2.0, 0.0, 0.0);
+    update(&mut app);
+    assert_close(next_frame(&app), (0.25, 0.0));
+    let _ = std::fs::remove_file(clip);
+}
@@ -0,0 +1,86 @@
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use raidillon_audio::output::NULL_SAMPLE_RATE;
+use raidillon_audio::{AudioStream, Mixer, PlayParams};
+
+const FRAMES: usize = 10_000;
+
+/// Mono `.wav` whose frame `i` is `(i + 1) * 2` in 16 bits, never silent.
+fn ramp_wav(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("raidillon_stream_{}_{name}.wav", std::process::id()));
+    let spec = hound::WavSpec {
+        channels:        1,
+        sample_rate:     NULL_SAMPLE_RATE,
+        bits_per_sample: 16,
+        sample_format:   hound::SampleFormat::Int,
+    };
+    let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+    for i in 0..FRAMES {
+        writer.write_sample(((i + 1) * 2) as i16).unwrap();
+    }
+    writer.finalize().unwrap();
+    path
+}
+
+/// Frame `i` of the ramp as the mixer plays it.
+fn ramp(i: usize) -> f32 {
+    ((i % FRAMES + 1) * 2) as f32 / 32768.0
+}
+
+/// Mix until at least `frames` non-silent frames were played or the voice
+/// ended, and return their left samples. Silence means the decoder was
+/// behind.
+fn play(mixer: &mut Mixer, frames: usize) -> Vec<f32> {
+    let start = Instant::now();
+    let mut played = Vec::new();
+    let mut out = vec![0.0; 512];
+    while played.len() < frames && mixer.voice_count() > 0 {
+        assert!(start.elapsed() < Duration::from_secs(10), "stream stalled after {} frames", played.len());
+        mixer.mix(&mut out);
+        let before = played.len();
+        played.extend(out.chunks_exact(2).map(|f| f[0]).filter(|&s| s != 0.0));
+        if played.len() == before {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+    played
+}
+
+#[test]
+fn stream_plays_the_file_once() {
+    let path = ramp_wav("once");
+    let mut mixer = Mixer::new(NULL_SAMPLE_RATE);
+    let voice = mixer.play_stream(AudioStream::open(&path).unwrap(), PlayParams::default());
+
+    let played = play(&mut mixer, usize::MAX);
+    assert!(!mixer.is_playing(voice));
+    assert_eq!(played.len(), FRAMES);
+    assert!(played.iter().enumerate().all(|(i, &s)| s == ramp(i)));
+    let _ = std::fs::remove_file(path);
+}
+
+#[test]
+fn looping_stream_starts_over() {
+    let path = ramp_wav("loop");
+    let mut mixer = Mixer::new(NULL_SAMPLE_RATE);
+    let params = PlayParams { looping: true, ..Default::default() };
+    let voice = mixer.play_stream(AudioStream::open(&path).unwrap(), params);
+
+    let played = play(&mut mixer, FRAMES * 2 + 100);
+    assert!(mixer.is_playing(voice));
+    assert!(played.iter().enumerate().all(|(i, &s)| s == ramp(i)));
+
+    // Stops at the end of the current pass once no longer looping.
+    mixer.params_mut(voice).unwrap().looping = false;
+    let rest = play(&mut mixer, usize::MAX);
+    assert!(!mixer.is_playing(voice));
+    assert_eq!(played.len() + rest.len(), FRAMES * 3);
+    assert!(rest.iter().enumerate().all(|(i, &s)| s == ramp(played.len() + i)));
+    let _ = std::fs::remove_file(path);
+}
+
+#[test]
+fn missing_file_fails_to_open() {
+    assert!(AudioStream::open("does/not/exist.wav").is_err());
+}
@@ -0,0 +1,134 @@
+use glam::{Mat4, Vec3};
+
+/// Half-line from `origin` along `dir`. `dir` is normally unit length; a ray
+/// moved into an object's local space with [`Ray::transformed`] keeps the
+/// scaled direction so hit distances stay in the original units.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Ray {
+    pub origin: Vec3,
+    pub dir:    Vec3,
+}
+
+impl Ray {
+    /// `dir` is normalized.
+    pub fn new(origin: Vec3, dir: Vec3) -> Self {
+        Self { origin, dir: dir.normalize() }
+    }
+
+    /// This ray in the space `matrix` maps into, e.g. an inverse model
+    /// matrix. `dir` is not renormalized.
+    pub fn transformed(&self, matrix: &Mat4) -> Self {
+        Self {
+            origin: matrix.transform_point3(self.origin),
+            dir:    matrix.transform_vector3(self.dir),
+        }
+    }
+
+    pub fn at(&self, t: f32) -> Vec3 {
+        self.origin + self.dir * t
+    }
+
+    /// Distance along the ray to the plane through `point` with `normal`, if
+    /// the plane is hit in front of the origin.
+    pub fn intersect_plane(&self, point: Vec3, normal: Vec3) -> Option<f32> {
+        let denom = self.dir.dot(normal);
+        if denom.abs() < 1e-6 {
+            return None;
+        }
+        let t = (point - self.origin).dot(normal) / denom;
+        (t >= 0.0).then_some(t)
+    }
+
+    /// Parameters `(t_ray, t_line)` of the closest points between the ray and
+    /// the infinite line through `point` along `dir`. `None` when parallel.
+    pub fn closest_to_line(&self, point: Vec3, dir: Vec3) -> Option<(f32, f32)> {
+        let r = self.origin - point;
+        let b = self.dir.dot(dir);
+        let c = dir.dot(dir);
+        let d = self.dir.dot(r);
+        let e = dir.dot(r);
+        let denom = c - b * b;
+        if denom.abs() < 1e-6 {
+            return None;
+        }
+        Some(((b * e - c * d) / denom, (e - b * d) / denom))
+    }
+
+    /// Entry distance into `aabb`, or 0 when the origin is inside.
+    pub fn intersect_aabb(&self, aabb: &Aabb) -> Option<f32> {
+        let inv = self.dir.recip();
+        let t0 = (aabb.min - self.origin) * inv;
+        let t1 = (aabb.max - self.origin) * inv;
+        let near = t0.min(t1).max_element().max(0.0);
+        let far = t0.max(t1).min_element();
+        (near <= far).then_some(near)
+    }
+
+    /// Two-sided Möller–Trumbore ray/triangle test.
+    pub fn intersect_triangle(&self, a: Vec3, b: Vec3, c: Vec3) -> Option<f32> {
+        let ab = b - a;
+        let ac = c - a;
+        let p = self.dir.cross(ac);
+        let det = ab.dot(p);
+        if det.abs() < 1e-8 {
+            return None;
+        }
+        let inv_det = det.recip();
+        let s = self.origin - a;
+        let u = s.dot(p) * inv_det;
+        if !(0.0..=1.0).contains(&u) {
+            return None;
+        }
+        let q = s.cross(ab);
+        let v = self.dir.dot(q) * inv_det;
+        if v < 0.0 || u + v > 1.0 {
+            return None;
+        }
+        let t = ac.dot(q) * inv_det;
+        (t >= 0.0).then_some(t)
+    }
+}
+
+/// Axis-aligned bounding box.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Aabb {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+impl Aabb {
+    /// Smallest box containing `points`; an empty box at the origin if there
+    /// are none.
+    pub fn from_points(points: impl IntoIterator<Item = Vec3>) -> Self {
+        let mut points = points.into_iter();
+        let Some(first) = points.next() else {
+            return Self { min: Vec3::ZERO, max: Vec3::ZERO };
+        };
+        points.fold(Self { min: first, max: first }, |aabb, p| Self {
+            min: aabb.min.min(p),
+            max: aabb.max.max(p),
+        })
+    }
+
+    pub fn center(&self) -> Vec3 {
+        (self.min + self.max) * 0.5
+    }
+
+    pub fn half_extents(&self) -> Vec3 {
+        (self.max - self.min) * 0.5
+    }
+
+    /// Bounds of this box after transforming it by `matrix`.
+    pub fn transformed(&self, matrix: &Mat4) -> Self {
+        let center = matrix.transform_point3(self.center());
+        let half = self.half_extents();
+        let extent = matrix.x_axis.truncate().abs() * half.x
+            + matrix.y_axis.truncate().abs() * half.y
+            + matrix.z_axis.truncate().abs() * half.z;
+        Self { min: center - extent, max: center + extent }
+    }
+
+    pub fn intersects(&self, other: &Aabb) -> bool {
+        self.min.cmple(other.max).all() && other.min.cmple(self.max).all()
+    }
+}
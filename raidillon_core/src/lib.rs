@@ -1,3 +1,8 @@
+pub mod geometry;
+pub mod mesh;
+pub mod profiler;
 pub mod time;
 
+pub use geometry::{Aabb, Ray};
+pub use mesh::{load_gltf_geometry, GeometryPart, MeshGeometry};
 pub use time::Time;
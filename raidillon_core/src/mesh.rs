@@ -0,0 +1,76 @@
+//! CPU-side triangle meshes and glTF geometry loading, shared by rendering,
+//! picking and physics. Nothing here touches the GPU.
+
+use std::fmt::Debug;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use glam::Vec3;
+
+use crate::geometry::{Aabb, Ray};
+
+/// Vertex positions and triangle indices of a mesh, with its bounds.
+#[derive(Clone, Debug)]
+pub struct MeshGeometry {
+    pub positions: Vec<Vec3>,
+    pub indices:   Vec<u32>,
+    pub aabb:      Aabb,
+}
+
+impl MeshGeometry {
+    pub fn new(positions: Vec<Vec3>, indices: Vec<u32>) -> Self {
+        let aabb = Aabb::from_points(positions.iter().copied());
+        Self { positions, indices, aabb }
+    }
+
+    /// Distance to the closest triangle hit by a ray in mesh space.
+    pub fn raycast(&self, ray: &Ray) -> Option<f32> {
+        ray.intersect_aabb(&self.aabb)?;
+        self.indices
+            .chunks_exact(3)
+            .filter_map(|tri| {
+                let [a, b, c] = [tri[0], tri[1], tri[2]].map(|i| self.positions[i as usize]);
+                ray.intersect_triangle(a, b, c)
+            })
+            .min_by(f32::total_cmp)
+    }
+}
+
+/// Triangles of one glTF primitive, for collision meshes.
+#[derive(Clone, Debug, Default)]
+pub struct GeometryPart {
+    pub positions: Vec<Vec3>,
+    pub indices:   Vec<u32>,
+    /// Name of the primitive's material.
+    pub material:  Option<String>,
+    /// The material's `extras` as JSON text.
+    pub extras:    Option<String>,
+}
+
+/// Positions and triangle indices of every primitive of every mesh, with
+/// their material, for collision meshes. Images are not decoded.
+pub fn load_gltf_geometry<P>(path: P) -> Result<Vec<GeometryPart>>
+where
+    P: AsRef<Path> + Debug,
+{
+    let path = path.as_ref();
+    let gltf = gltf::Gltf::open(path).context("failed to open glTF file")?;
+    let buffers = gltf::import_buffers(&gltf.document, path.parent(), gltf.blob.clone())
+        .context("failed to load glTF buffers")?;
+
+    let mut parts = Vec::new();
+    for primitive in gltf.document.meshes().flat_map(|mesh| mesh.primitives()) {
+        let reader   = primitive.reader(|buf| Some(&buffers[buf.index()].0));
+        let material = primitive.material();
+        parts.push(GeometryPart {
+            positions: reader.read_positions().context("missing POSITION")?.map(Vec3::from).collect(),
+            indices:   reader.read_indices().context("missing indices")?.into_u32().collect(),
+            material:  material.name().map(str::to_owned),
+            extras:    material.extras().as_ref().map(|raw| raw.get().to_owned()),
+        });
+    }
+    if parts.is_empty() {
+        bail!("glTF has no meshes");
+    }
+    Ok(parts)
+}
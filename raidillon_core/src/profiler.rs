@@ -0,0 +1,231 @@
+//! Frame profiler.
+//!
+//! Code marks regions with [`profile_scope!`](crate::profile_scope) and the
+//! runner brackets every frame with [`begin_frame`]/[`end_frame`]. Finished
+//! frames are kept in a short history for the profiler overlay and can be
+//! exported as a Chrome trace (`chrome://tracing`, Perfetto).
+//!
+//! GPU pass times are reported with [`record_gpu`]. Timer queries resolve a
+//! few frames late, so they land on whichever frame is being recorded when
+//! they come in.
+//!
+//! Scopes are recorded per thread; only the thread driving the frames is
+//! collected.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::fmt::Write;
+use std::time::{Duration, Instant};
+
+/// Number of finished frames kept.
+pub const HISTORY_LEN: usize = 300;
+
+/// A timed region of CPU code.
+#[derive(Clone, Debug)]
+pub struct ScopeTiming {
+    pub name:     &'static str,
+    /// Offset from the start of the frame.
+    pub start:    Duration,
+    pub duration: Duration,
+    /// Nesting level, 0 for outermost scopes.
+    pub depth:    u32,
+}
+
+/// A timed GPU pass.
+#[derive(Clone, Debug)]
+pub struct GpuTiming {
+    pub name:     &'static str,
+    pub duration: Duration,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct FrameProfile {
+    pub index:    u64,
+    /// Offset from the creation of the profiler.
+    pub start:    Duration,
+    pub duration: Duration,
+    /// Sorted by start.
+    pub scopes:   Vec<ScopeTiming>,
+    pub gpu:      Vec<GpuTiming>,
+}
+
+struct Profiler {
+    enabled: bool,
+    origin:  Instant,
+    /// Frame being recorded and its start.
+    current: Option<(Instant, FrameProfile)>,
+    depth:   u32,
+    history: VecDeque<FrameProfile>,
+    frames:  u64,
+}
+
+impl Profiler {
+    fn new() -> Self {
+        Self {
+            enabled: true,
+            origin:  Instant::now(),
+            current: None,
+            depth:   0,
+            history: VecDeque::with_capacity(HISTORY_LEN),
+            frames:  0,
+        }
+    }
+}
+
+thread_local! {
+    static PROFILER: RefCell<Profiler> = RefCell::new(Profiler::new());
+}
+
+/// Start recording a frame, finishing the previous one if still open.
+pub fn begin_frame() {
+    end_frame();
+    PROFILER.with_borrow_mut(|p| {
+        if !p.enabled {
+            return;
+        }
+        let now = Instant::now();
+        let frame = FrameProfile { index: p.frames, start: now - p.origin, ..Default::default() };
+        p.frames += 1;
+        p.current = Some((now, frame));
+    });
+}
+
+/// Finish the frame being recorded and move it into the history.
+pub fn end_frame() {
+    PROFILER.with_borrow_mut(|p| {
+        let Some((start, mut frame)) = p.current.take() else {
+            return;
+        };
+        frame.duration = start.elapsed();
+        frame.scopes.sort_by_key(|s| s.start);
+        if p.history.len() == HISTORY_LEN {
+            p.history.pop_front();
+        }
+        p.history.push_back(frame);
+    });
+}
+
+/// Pause or resume recording. The history is kept while paused.
+pub fn set_enabled(enabled: bool) {
+    PROFILER.with_borrow_mut(|p| {
+        p.enabled = enabled;
+        if !enabled {
+            p.current = None;
+        }
+    });
+}
+
+pub fn is_enabled() -> bool {
+    PROFILER.with_borrow(|p| p.enabled)
+}
+
+/// Attach a GPU pass time to the frame being recorded.
+pub fn record_gpu(name: &'static str, duration: Duration) {
+    PROFILER.with_borrow_mut(|p| {
+        if let Some((_, frame)) = &mut p.current {
+            frame.gpu.push(GpuTiming { name, duration });
+        }
+    });
+}
+
+/// Run `f` on the finished frames, oldest first. `f` must not call back into
+/// the profiler.
+pub fn with_history<R>(f: impl FnOnce(&VecDeque<FrameProfile>) -> R) -> R {
+    PROFILER.with_borrow(|p| f(&p.history))
+}
+
+/// Time the region until the returned guard is dropped. Usually invoked
+/// through [`profile_scope!`](crate::profile_scope).
+pub fn scope(name: &'static str) -> ScopeGuard {
+    let depth = PROFILER.with_borrow_mut(|p| {
+        p.current.as_ref()?;
+        p.depth += 1;
+        Some(p.depth - 1)
+    });
+    ScopeGuard { name, start: Instant::now(), depth }
+}
+
+/// Records its scope on drop.
+#[must_use = "the scope ends when the guard is dropped"]
+pub struct ScopeGuard {
+    name:  &'static str,
+    start: Instant,
+    /// `None` when no frame was being recorded.
+    depth: Option<u32>,
+}
+
+impl Drop for ScopeGuard {
+    fn drop(&mut self) {
+        let Some(depth) = self.depth else {
+            return;
+        };
+        let duration = self.start.elapsed();
+        PROFILER.with_borrow_mut(|p| {
+            p.depth = depth;
+            if let Some((frame_start, frame)) = &mut p.current {
+                frame.scopes.push(ScopeTiming {
+                    name: self.name,
+                    start: self.start.saturating_duration_since(*frame_start),
+                    duration,
+                    depth,
+                });
+            }
+        });
+    }
+}
+
+/// Time the rest of the enclosing block under `name`.
+#[macro_export]
+macro_rules! profile_scope {
+    ($name:expr) => {
+        let _profile_scope = $crate::profiler::scope($name);
+    };
+}
+
+/// Serialize `frames` in the Chrome trace event format. CPU scopes go on
+/// thread 0; GPU passes, whose start times are unknown, are laid out back to
+/// back from the start of their frame on thread 1.
+pub fn chrome_trace<'a>(frames: impl IntoIterator<Item = &'a FrameProfile>) -> String {
+    let mut out = String::from("{\"traceEvents\":[\n");
+    out.push_str(r#"{"name":"thread_name","ph":"M","pid":0,"tid":0,"args":{"name":"CPU"}},"#);
+    out.push('\n');
+    out.push_str(r#"{"name":"thread_name","ph":"M","pid":0,"tid":1,"args":{"name":"GPU"}}"#);
+
+    let mut event = |name: &str, tid: u32, start: Duration, duration: Duration| {
+        let _ = write!(
+            out,
+            ",\n{{\"name\":\"{}\",\"ph\":\"X\",\"pid\":0,\"tid\":{tid},\"ts\":{:.3},\"dur\":{:.3}}}",
+            escape(name),
+            start.as_secs_f64() * 1e6,
+            duration.as_secs_f64() * 1e6,
+        );
+    };
+    for frame in frames {
+        event(&format!("frame {}", frame.index), 0, frame.start, frame.duration);
+        for scope in &frame.scopes {
+            event(scope.name, 0, frame.start + scope.start, scope.duration);
+        }
+        let mut gpu_start = frame.start;
+        for pass in &frame.gpu {
+            event(pass.name, 1, gpu_start, pass.duration);
+            gpu_start += pass.duration;
+        }
+    }
+    out.push_str("\n]}\n");
+    out
+}
+
+fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"'  => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if c.is_control() => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out
+}
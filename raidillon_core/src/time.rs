@@ -7,6 +7,12 @@ pub struct Time {
     total:  Duration,
 }
 
+impl Default for Time {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Time {
     pub fn new() -> Self {
         let now = Instant::now();
@@ -0,0 +1,169 @@
+//! Application builder tying the world, resources and schedule together.
+//!
+//! An [`App`] is assembled from [`Plugin`]s, systems and resources and then
+//! handed to a runner with [`App::run`]. The runner decides how frames are
+//! driven; a windowed one is installed by `raidillon_app::WinitPlugin`, the
+//! default just runs a single headless frame.
+
+use std::any::TypeId;
+use std::collections::HashSet;
+
+use hecs::World;
+use raidillon_core::Time;
+
+use crate::schedule::BoxedSystem;
+use crate::{ReflectRegistry, Resources, SceneRegistry, Schedule, Stage};
+
+/// A self-contained piece of engine or game functionality that registers its
+/// resources, systems and event types on an [`App`].
+pub trait Plugin: 'static {
+    fn build(&self, app: &mut App);
+}
+
+pub type Runner = Box<dyn FnOnce(App) -> anyhow::Result<()>>;
+
+pub struct App {
+    pub world:     World,
+    pub resources: Resources,
+    pub schedule:  Schedule,
+    startup:       Vec<BoxedSystem>,
+    shutdown:      Vec<BoxedSystem>,
+    runner:        Option<Runner>,
+    /// Types of the plugins added so far.
+    plugins:       HashSet<TypeId>,
+}
+
+impl Default for App {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl App {
+    /// Empty app with [`Time`], [`SceneRegistry`] and [`ReflectRegistry`]
+    /// resources.
+    pub fn new() -> Self {
+        let mut resources = Resources::new();
+        resources.insert(Time::new());
+        resources.insert(SceneRegistry::default());
+        resources.insert(ReflectRegistry::default());
+        Self {
+            world: World::new(),
+            resources,
+            schedule: Schedule::new(),
+            startup: Vec::new(),
+            shutdown: Vec::new(),
+            runner: None,
+            plugins: HashSet::new(),
+        }
+    }
+
+    /// Build `plugin` into this app. Adding a plugin of a type that was
+    /// already added is a no-op, so plugins can add the plugins they depend
+    /// on.
+    pub fn add_plugin<P: Plugin>(&mut self, plugin: P) -> &mut Self {
+        if self.plugins.insert(TypeId::of::<P>()) {
+            plugin.build(self);
+        }
+        self
+    }
+
+    pub fn has_plugin<P: Plugin>(&self) -> bool {
+        self.plugins.contains(&TypeId::of::<P>())
+    }
+
+    /// Add a system to `stage`. Use `app.schedule.add_system` directly when it
+    /// needs `before`/`after` constraints.
+    pub fn add_system<F>(&mut self, stage: Stage, name: &'static str, system: F) -> &mut Self
+    where
+        F: FnMut(&mut World, &mut Resources) + 'static,
+    {
+        self.schedule.add_system(stage, name, system);
+        self
+    }
+
+    /// Add a system that runs once before the first frame, in registration
+    /// order.
+    pub fn add_startup_system<F>(&mut self, system: F) -> &mut Self
+    where
+        F: FnMut(&mut World, &mut Resources) + 'static,
+    {
+        self.startup.push(Box::new(system));
+        self
+    }
+
+    /// Add a system that runs once after the last frame, e.g. to save
+    /// settings, in registration order.
+    pub fn add_shutdown_system<F>(&mut self, system: F) -> &mut Self
+    where
+        F: FnMut(&mut World, &mut Resources) + 'static,
+    {
+        self.shutdown.push(Box::new(system));
+        self
+    }
+
+    pub fn insert_resource<T: 'static>(&mut self, value: T) -> &mut Self {
+        self.resources.insert(value);
+        self
+    }
+
+    pub fn init_resource<T: Default + 'static>(&mut self) -> &mut Self {
+        self.resources.init::<T>();
+        self
+    }
+
+    pub fn add_event<T: 'static>(&mut self) -> &mut Self {
+        self.schedule.add_event::<T>(&mut self.resources);
+        self
+    }
+
+    pub fn set_runner<F>(&mut self, runner: F) -> &mut Self
+    where
+        F: FnOnce(App) -> anyhow::Result<()> + 'static,
+    {
+        self.runner = Some(Box::new(runner));
+        self
+    }
+
+    /// Run the startup systems against `world`. Runners that move the world
+    /// elsewhere (e.g. into an `ECSRenderer`) pass it back in here.
+    pub fn run_startup(&mut self, world: &mut World) {
+        for mut system in self.startup.drain(..) {
+            system(world, &mut self.resources);
+        }
+    }
+
+    /// Run the shutdown systems against `world`.
+    pub fn run_shutdown(&mut self, world: &mut World) {
+        for mut system in self.shutdown.drain(..) {
+            system(world, &mut self.resources);
+        }
+    }
+
+    /// Tick [`Time`] and run one frame of the schedule against `world`.
+    pub fn update(&mut self, world: &mut World) {
+        let dt = {
+            let mut time = self.resources.fetch_mut::<Time>();
+            time.tick();
+            time.delta_seconds()
+        };
+        self.schedule.run(world, &mut self.resources, dt);
+    }
+
+    /// Hand the app over to its runner. The builder is left empty.
+    pub fn run(&mut self) -> anyhow::Result<()> {
+        let mut app = std::mem::take(self);
+        let runner = app.runner.take().unwrap_or_else(|| Box::new(run_once));
+        runner(app)
+    }
+}
+
+/// Default runner: startup, a single frame and shutdown, no window.
+fn run_once(mut app: App) -> anyhow::Result<()> {
+    let mut world = std::mem::take(&mut app.world);
+    app.run_startup(&mut world);
+    app.update(&mut world);
+    app.run_shutdown(&mut world);
+    app.world = world;
+    Ok(())
+}
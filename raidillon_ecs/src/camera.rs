@@ -0,0 +1,61 @@
+use glam::{Mat4, Vec2, Vec3};
+use raidillon_core::Ray;
+use serde::{Deserialize, Serialize};
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct Camera {
+    pub eye:    Vec3,
+    pub center: Vec3,
+    pub up:     Vec3,
+    pub fovy:   f32,
+    pub aspect: f32,
+    pub znear:  f32,
+    pub zfar:   f32,
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Self {
+            eye:    Vec3::new(0.0, 0.0, 2.0),
+            center: Vec3::ZERO,
+            up:     Vec3::Y,
+            fovy:   60_f32.to_radians(),
+            aspect: 16.0 / 9.0,
+            znear:  0.1,
+            zfar:   100.0,
+        }
+    }
+}
+
+impl Camera {
+    pub fn view(&self) -> Mat4 {
+        Mat4::look_at_rh(self.eye, self.center, self.up)
+    }
+    pub fn projection(&self) -> Mat4 {
+        Mat4::perspective_rh(self.fovy, self.aspect, self.znear, self.zfar)
+    }
+    pub fn view_proj(&self) -> Mat4 {
+        self.projection() * self.view()
+    }
+
+    /// World space ray through the pixel `screen` of a viewport `size` pixels
+    /// large, origin at the top left.
+    pub fn screen_ray(&self, screen: Vec2, size: Vec2) -> Ray {
+        let ndc = Vec2::new(screen.x / size.x * 2.0 - 1.0, 1.0 - screen.y / size.y * 2.0);
+        let inv = self.view_proj().inverse();
+        let near = inv.project_point3(ndc.extend(0.0));
+        let far = inv.project_point3(ndc.extend(1.0));
+        Ray::new(near, far - near)
+    }
+
+    /// Pixel position of `point` in a viewport `size` pixels large, or `None`
+    /// when it is behind the camera.
+    pub fn world_to_screen(&self, point: Vec3, size: Vec2) -> Option<Vec2> {
+        let clip = self.view_proj() * point.extend(1.0);
+        if clip.w <= 1e-5 {
+            return None;
+        }
+        let ndc = clip.truncate() / clip.w;
+        Some(Vec2::new((ndc.x + 1.0) * 0.5 * size.x, (1.0 - ndc.y) * 0.5 * size.y))
+    }
+}
@@ -0,0 +1,131 @@
+//! Double-buffered event channels between systems.
+//!
+//! [`Events<T>`] is stored as a resource. Writers call [`Events::send`],
+//! readers keep an [`EventReader<T>`] which remembers how far it has read.
+//! [`Events::update`] runs once per frame and swaps the buffers, so an event
+//! stays readable for two frames before it is dropped. That way a system
+//! running earlier in the frame than the writer still gets to see it.
+
+use std::any::type_name;
+use std::marker::PhantomData;
+
+use crate::{Resources, Schedule, Stage};
+
+pub struct Events<T> {
+    previous:       Vec<T>,
+    current:        Vec<T>,
+    /// Id of the first event in `previous`.
+    start_previous: usize,
+    /// Id of the first event in `current`.
+    start_current:  usize,
+    /// Id the next sent event will get.
+    event_count:    usize,
+}
+
+impl<T> Default for Events<T> {
+    fn default() -> Self {
+        Self {
+            previous:       Vec::new(),
+            current:        Vec::new(),
+            start_previous: 0,
+            start_current:  0,
+            event_count:    0,
+        }
+    }
+}
+
+impl<T> Events<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn send(&mut self, event: T) {
+        self.current.push(event);
+        self.event_count += 1;
+    }
+
+    /// Swap buffers, dropping the events sent two updates ago.
+    pub fn update(&mut self) {
+        self.previous = std::mem::take(&mut self.current);
+        self.start_previous = self.start_current;
+        self.start_current = self.event_count;
+    }
+
+    /// Number of events currently buffered.
+    pub fn len(&self) -> usize {
+        self.previous.len() + self.current.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&mut self) {
+        self.start_previous = self.event_count;
+        self.start_current = self.event_count;
+        self.previous.clear();
+        self.current.clear();
+    }
+
+    /// Remove and return every buffered event, bypassing readers.
+    pub fn drain(&mut self) -> impl Iterator<Item = T> + '_ {
+        self.start_previous = self.event_count;
+        self.start_current = self.event_count;
+        self.previous.drain(..).chain(self.current.drain(..))
+    }
+
+    /// Reader that only sees events sent from now on.
+    pub fn reader(&self) -> EventReader<T> {
+        EventReader { last: self.event_count, _marker: PhantomData }
+    }
+}
+
+/// Cursor into an [`Events<T>`] channel. Each reader sees every event once.
+pub struct EventReader<T> {
+    last:    usize,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Default for EventReader<T> {
+    /// A reader that starts at the oldest event still buffered.
+    fn default() -> Self {
+        Self { last: 0, _marker: PhantomData }
+    }
+}
+
+impl<T> EventReader<T> {
+    /// Events sent since the last call. Events that were already dropped
+    /// because the reader fell more than two updates behind are skipped.
+    pub fn read<'a>(&mut self, events: &'a Events<T>) -> impl Iterator<Item = &'a T> + 'a {
+        let skip_previous = self.last.saturating_sub(events.start_previous).min(events.previous.len());
+        let skip_current  = self.last.saturating_sub(events.start_current).min(events.current.len());
+        self.last = events.event_count;
+        events.previous[skip_previous..]
+            .iter()
+            .chain(events.current[skip_current..].iter())
+    }
+
+    /// Mark everything as read without looking at it.
+    pub fn clear(&mut self, events: &Events<T>) {
+        self.last = events.event_count;
+    }
+}
+
+/// System that advances the `Events<T>` buffers once per frame.
+pub fn update_events<T: 'static>(_world: &mut hecs::World, resources: &mut Resources) {
+    if let Some(mut events) = resources.get_mut::<Events<T>>() {
+        events.update();
+    }
+}
+
+impl Schedule {
+    /// Register an `Events<T>` resource together with the system updating it
+    /// at the start of every frame. Registering the same type twice is a no-op.
+    pub fn add_event<T: 'static>(&mut self, resources: &mut Resources) {
+        if resources.contains::<Events<T>>() {
+            return;
+        }
+        resources.insert(Events::<T>::new());
+        self.add_system(Stage::PreUpdate, type_name::<Events<T>>(), update_events::<T>);
+    }
+}
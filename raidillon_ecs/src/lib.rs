@@ -1,13 +1,44 @@
 use glam::{Mat4, Quat, Vec3};
+use hecs::Entity;
+use raidillon_core::Aabb;
+use serde::{Deserialize, Serialize};
 
-#[derive(Copy, Clone)]
+pub mod app;
+pub mod camera;
+pub mod events;
+pub mod reflect;
+pub mod resources;
+pub mod scene;
+pub mod schedule;
+
+pub use app::{App, Plugin};
+pub use camera::Camera;
+pub use events::{EventReader, Events};
+pub use reflect::{ComponentInfo, FieldInfo, FieldType, FieldValue, Reflect, ReflectRegistry};
+pub use resources::Resources;
+pub use scene::{Scene, SceneRegistry};
+pub use schedule::{Schedule, Stage};
+
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Transform {
     pub translation: Vec3,
     pub rotation:    Quat,
     pub scale:       Vec3,
 }
 
+impl Default for Transform {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
 impl Transform {
+    pub const IDENTITY: Self = Self {
+        translation: Vec3::ZERO,
+        rotation:    Quat::IDENTITY,
+        scale:       Vec3::ONE,
+    };
+
     pub fn matrix(&self) -> Mat4 {
         Mat4::from_scale_rotation_translation(self.scale, self.rotation, self.translation)
     }
@@ -15,3 +46,30 @@ impl Transform {
 
 #[derive(Clone)]
 pub struct ModelHandle(pub usize);
+
+/// Model-space bounds of an entity's model, kept next to its `ModelHandle`
+/// for systems that have no access to the renderer.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct MeshBounds(pub Aabb);
+
+/// Path of a glTF model to load for this entity. The renderer resolves it into
+/// a [`ModelHandle`] the first time it sees the entity.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ModelPath(pub String);
+
+/// Human readable entity name, shown by editor tooling.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Name(pub String);
+
+/// Gameplay label for an entity, e.g. "player", matched by triggers and
+/// scripts. Unlike [`Name`] it need not be unique.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Tag(pub String);
+
+/// Entity this one is grouped under in the editor hierarchy. Transforms stay
+/// in world space; the link is not saved with scenes.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Parent(pub Entity);
@@ -0,0 +1,304 @@
+//! Runtime type information for components.
+//!
+//! Components describe their editable fields once in the [`ReflectRegistry`]:
+//! name, type and an optional value range. Generic tooling (inspectors,
+//! consoles, serializers) can then list and read/write those fields on any
+//! entity without knowing the concrete component type.
+
+use std::any::{Any, TypeId};
+use std::marker::PhantomData;
+use std::ops::RangeInclusive;
+
+use glam::{Quat, Vec3};
+use hecs::{Component, Entity, World};
+
+use crate::{App, ModelPath, Name, Parent, Tag, Transform};
+
+/// Type of a reflected field.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum FieldType {
+    Bool,
+    F32,
+    Vec3,
+    Quat,
+    String,
+}
+
+/// Dynamically typed field value.
+#[derive(Clone, Debug, PartialEq)]
+pub enum FieldValue {
+    Bool(bool),
+    F32(f32),
+    Vec3(Vec3),
+    Quat(Quat),
+    String(String),
+}
+
+impl FieldValue {
+    pub fn ty(&self) -> FieldType {
+        match self {
+            FieldValue::Bool(_)   => FieldType::Bool,
+            FieldValue::F32(_)    => FieldType::F32,
+            FieldValue::Vec3(_)   => FieldType::Vec3,
+            FieldValue::Quat(_)   => FieldType::Quat,
+            FieldValue::String(_) => FieldType::String,
+        }
+    }
+}
+
+/// Rust types that can be exposed as a reflected field.
+pub trait Reflect: Sized {
+    const TYPE: FieldType;
+    fn to_field(&self) -> FieldValue;
+    fn from_field(value: FieldValue) -> Option<Self>;
+}
+
+macro_rules! impl_reflect {
+    ($ty:ty, $variant:ident) => {
+        impl Reflect for $ty {
+            const TYPE: FieldType = FieldType::$variant;
+            fn to_field(&self) -> FieldValue {
+                FieldValue::$variant(self.clone())
+            }
+            fn from_field(value: FieldValue) -> Option<Self> {
+                match value {
+                    FieldValue::$variant(v) => Some(v),
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+impl_reflect!(bool, Bool);
+impl_reflect!(f32, F32);
+impl_reflect!(Vec3, Vec3);
+impl_reflect!(Quat, Quat);
+impl_reflect!(String, String);
+
+type FieldGetter = Box<dyn Fn(&World, Entity) -> Option<FieldValue>>;
+type FieldSetter = Box<dyn Fn(&World, Entity, FieldValue) -> bool>;
+
+/// Description of a single field of a reflected component.
+pub struct FieldInfo {
+    pub name:  &'static str,
+    pub ty:    FieldType,
+    /// Valid range for numeric fields (applied per component for `Vec3`).
+    pub range: Option<RangeInclusive<f32>>,
+    get:       FieldGetter,
+    set:       FieldSetter,
+}
+
+impl FieldInfo {
+    pub fn get(&self, world: &World, entity: Entity) -> Option<FieldValue> {
+        (self.get)(world, entity)
+    }
+
+    /// Write `value`, clamped to the field's range. Returns `false` when the
+    /// entity lacks the component or the value has the wrong type.
+    pub fn set(&self, world: &World, entity: Entity, value: FieldValue) -> bool {
+        let value = match (&self.range, value) {
+            (Some(r), FieldValue::F32(v))  => FieldValue::F32(v.clamp(*r.start(), *r.end())),
+            (Some(r), FieldValue::Vec3(v)) => FieldValue::Vec3(v.clamp(Vec3::splat(*r.start()), Vec3::splat(*r.end()))),
+            (_, v) => v,
+        };
+        (self.set)(world, entity, value)
+    }
+}
+
+/// Description of a reflected component type.
+pub struct ComponentInfo {
+    pub name:    &'static str,
+    pub type_id: TypeId,
+    pub fields:  Vec<FieldInfo>,
+    has:         fn(&World, Entity) -> bool,
+    take:        fn(&mut World, Entity) -> Option<Box<dyn Any>>,
+    restore:     fn(&mut World, Entity, Box<dyn Any>),
+    /// Set by [`ComponentBuilder::insertable`].
+    insert:      Option<fn(&mut World, Entity)>,
+}
+
+impl ComponentInfo {
+    pub fn has(&self, world: &World, entity: Entity) -> bool {
+        (self.has)(world, entity)
+    }
+
+    pub fn field(&self, name: &str) -> Option<&FieldInfo> {
+        self.fields.iter().find(|f| f.name == name)
+    }
+
+    /// Whether tooling may add this component with its default value.
+    pub fn can_insert(&self) -> bool {
+        self.insert.is_some()
+    }
+
+    /// Add the default value of this component to `entity`, replacing any
+    /// existing one. Returns `false` if the component is not insertable.
+    pub fn insert_default(&self, world: &mut World, entity: Entity) -> bool {
+        match self.insert {
+            Some(insert) => {
+                insert(world, entity);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn remove(&self, world: &mut World, entity: Entity) {
+        self.take(world, entity);
+    }
+
+    /// Remove the component from `entity` and return it, type-erased, for a
+    /// later [`restore`](Self::restore).
+    pub fn take(&self, world: &mut World, entity: Entity) -> Option<Box<dyn Any>> {
+        (self.take)(world, entity)
+    }
+
+    /// Insert a component previously returned by [`take`](Self::take).
+    /// Values of another type are ignored.
+    pub fn restore(&self, world: &mut World, entity: Entity, value: Box<dyn Any>) {
+        (self.restore)(world, entity, value)
+    }
+}
+
+/// All reflected component types, in registration order.
+pub struct ReflectRegistry {
+    components: Vec<ComponentInfo>,
+}
+
+impl Default for ReflectRegistry {
+    /// Registry with the components defined in this crate.
+    fn default() -> Self {
+        let mut registry = Self { components: Vec::new() };
+        registry
+            .register::<Name>("Name")
+            .insertable()
+            .field("name", |n| &mut n.0);
+        registry
+            .register::<Transform>("Transform")
+            .insertable()
+            .field("translation", |t| &mut t.translation)
+            .field("rotation", |t| &mut t.rotation)
+            .field_range("scale", 0.0001..=1000.0, |t| &mut t.scale);
+        registry
+            .register::<ModelPath>("ModelPath")
+            .insertable()
+            .field("path", |p| &mut p.0);
+        registry
+            .register::<Tag>("Tag")
+            .insertable()
+            .field("tag", |t| &mut t.0);
+        // No editable fields; registered so despawn and undo keep it.
+        registry.register::<Parent>("Parent");
+        registry
+    }
+}
+
+impl ReflectRegistry {
+    /// Start describing component `T` under `name`.
+    pub fn register<T: Component>(&mut self, name: &'static str) -> ComponentBuilder<'_, T> {
+        assert!(
+            self.components.iter().all(|c| c.name != name),
+            "reflected component `{name}` registered twice"
+        );
+        self.components.push(ComponentInfo {
+            name,
+            type_id: TypeId::of::<T>(),
+            fields:  Vec::new(),
+            has:     |world, entity| world.satisfies::<&T>(entity).unwrap_or(false),
+            take:    |world, entity| {
+                world.remove_one::<T>(entity).ok().map(|c| Box::new(c) as Box<dyn Any>)
+            },
+            restore: |world, entity, value| {
+                if let Ok(component) = value.downcast::<T>() {
+                    let _ = world.insert_one(entity, *component);
+                }
+            },
+            insert:  None,
+        });
+        ComponentBuilder { info: self.components.last_mut().unwrap(), _marker: PhantomData }
+    }
+
+    pub fn components(&self) -> impl Iterator<Item = &ComponentInfo> {
+        self.components.iter()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&ComponentInfo> {
+        self.components.iter().find(|c| c.name == name)
+    }
+
+    pub fn get_by_type<T: 'static>(&self) -> Option<&ComponentInfo> {
+        self.components.iter().find(|c| c.type_id == TypeId::of::<T>())
+    }
+
+    /// Reflected components present on `entity`.
+    pub fn components_of<'a>(&'a self, world: &'a World, entity: Entity) -> impl Iterator<Item = &'a ComponentInfo> + 'a {
+        self.components.iter().filter(move |c| c.has(world, entity))
+    }
+}
+
+/// Returned by [`ReflectRegistry::register`] to describe fields.
+pub struct ComponentBuilder<'a, T> {
+    info:    &'a mut ComponentInfo,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T: Component> ComponentBuilder<'_, T> {
+    /// Allow tooling to add `T::default()` to entities.
+    pub fn insertable(self) -> Self
+    where
+        T: Default,
+    {
+        self.info.insert = Some(|world, entity| {
+            let _ = world.insert_one(entity, T::default());
+        });
+        self
+    }
+
+    pub fn field<F: Reflect + 'static>(self, name: &'static str, access: fn(&mut T) -> &mut F) -> Self {
+        self.add_field(name, None, access)
+    }
+
+    pub fn field_range<F: Reflect + 'static>(
+        self,
+        name: &'static str,
+        range: RangeInclusive<f32>,
+        access: fn(&mut T) -> &mut F,
+    ) -> Self {
+        self.add_field(name, Some(range), access)
+    }
+
+    fn add_field<F: Reflect + 'static>(
+        self,
+        name: &'static str,
+        range: Option<RangeInclusive<f32>>,
+        access: fn(&mut T) -> &mut F,
+    ) -> Self {
+        self.info.fields.push(FieldInfo {
+            name,
+            ty: F::TYPE,
+            range,
+            get: Box::new(move |world, entity| {
+                let mut component = world.get::<&mut T>(entity).ok()?;
+                Some(access(&mut component).to_field())
+            }),
+            set: Box::new(move |world, entity, value| {
+                let (Ok(mut component), Some(value)) = (world.get::<&mut T>(entity), F::from_field(value)) else {
+                    return false;
+                };
+                *access(&mut component) = value;
+                true
+            }),
+        });
+        self
+    }
+}
+
+impl App {
+    /// Describe the fields of component `T` for runtime tooling.
+    pub fn reflect<T: Component>(&mut self, name: &'static str, describe: impl FnOnce(ComponentBuilder<'_, T>)) -> &mut Self {
+        self.resources.init::<ReflectRegistry>();
+        describe(self.resources.fetch_mut::<ReflectRegistry>().register::<T>(name));
+        self
+    }
+}
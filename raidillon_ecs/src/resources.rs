@@ -0,0 +1,67 @@
+//! Type-keyed storage for global singletons (time, input, settings…).
+//!
+//! Every resource sits behind its own `RefCell`, so a system holding
+//! `&Resources` can borrow several different resources at once, mutably or
+//! not. Borrowing the same resource mutably twice panics, exactly like
+//! `RefCell` would.
+
+use std::any::{type_name, Any, TypeId};
+use std::cell::{Ref, RefCell, RefMut};
+use std::collections::HashMap;
+
+#[derive(Default)]
+pub struct Resources {
+    map: HashMap<TypeId, RefCell<Box<dyn Any>>>,
+}
+
+impl Resources {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Insert `value`, returning the previous resource of the same type.
+    pub fn insert<T: 'static>(&mut self, value: T) -> Option<T> {
+        self.map
+            .insert(TypeId::of::<T>(), RefCell::new(Box::new(value)))
+            .map(|old| *old.into_inner().downcast::<T>().unwrap())
+    }
+
+    /// Insert `T::default()` unless a `T` is already present.
+    pub fn init<T: Default + 'static>(&mut self) {
+        self.map
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| RefCell::new(Box::new(T::default())));
+    }
+
+    pub fn remove<T: 'static>(&mut self) -> Option<T> {
+        self.map
+            .remove(&TypeId::of::<T>())
+            .map(|old| *old.into_inner().downcast::<T>().unwrap())
+    }
+
+    pub fn contains<T: 'static>(&self) -> bool {
+        self.map.contains_key(&TypeId::of::<T>())
+    }
+
+    pub fn get<T: 'static>(&self) -> Option<Ref<'_, T>> {
+        let cell = self.map.get(&TypeId::of::<T>())?;
+        Some(Ref::map(cell.borrow(), |b| b.downcast_ref::<T>().unwrap()))
+    }
+
+    pub fn get_mut<T: 'static>(&self) -> Option<RefMut<'_, T>> {
+        let cell = self.map.get(&TypeId::of::<T>())?;
+        Some(RefMut::map(cell.borrow_mut(), |b| b.downcast_mut::<T>().unwrap()))
+    }
+
+    /// Like [`Resources::get`] but panics with the type name when missing.
+    pub fn fetch<T: 'static>(&self) -> Ref<'_, T> {
+        self.get::<T>()
+            .unwrap_or_else(|| panic!("resource `{}` not registered", type_name::<T>()))
+    }
+
+    /// Like [`Resources::get_mut`] but panics with the type name when missing.
+    pub fn fetch_mut<T: 'static>(&self) -> RefMut<'_, T> {
+        self.get_mut::<T>()
+            .unwrap_or_else(|| panic!("resource `{}` not registered", type_name::<T>()))
+    }
+}
@@ -0,0 +1,200 @@
+//! Saving and loading worlds as scene files.
+//!
+//! Only components registered in the [`SceneRegistry`] are persisted, each
+//! under its registered name. Anything renderer specific, like a
+//! [`ModelHandle`](crate::ModelHandle), is left out; models are referenced by
+//! [`ModelPath`](crate::ModelPath) and loaded again by the renderer.
+//!
+//! Scenes are stored as RON or JSON, picked by file extension.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use hecs::{Component, Entity, World};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use ron::Value;
+
+use crate::{App, ModelPath, Name, Tag, Transform};
+
+/// Serialized form of a world.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Scene {
+    pub entities: Vec<SceneEntity>,
+}
+
+/// One entity: registered component name → component data.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct SceneEntity {
+    pub components: BTreeMap<String, Value>,
+}
+
+impl Scene {
+    pub fn to_ron(&self) -> Result<String> {
+        let config = ron::ser::PrettyConfig::default().compact_arrays(true);
+        Ok(ron::ser::to_string_pretty(self, config)?)
+    }
+
+    pub fn from_ron(src: &str) -> Result<Self> {
+        Ok(ron::from_str(src)?)
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn from_json(src: &str) -> Result<Self> {
+        Ok(serde_json::from_str(src)?)
+    }
+
+    /// Write to `path` as `.ron` or `.json`.
+    pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        let text = match Format::of(path)? {
+            Format::Ron  => self.to_ron()?,
+            Format::Json => self.to_json()?,
+        };
+        std::fs::write(path, text).with_context(|| format!("writing scene {path:?}"))
+    }
+
+    /// Read a `.ron` or `.json` scene from `path`.
+    pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path).with_context(|| format!("reading scene {path:?}"))?;
+        match Format::of(path)? {
+            Format::Ron  => Self::from_ron(&text),
+            Format::Json => Self::from_json(&text),
+        }
+        .with_context(|| format!("parsing scene {path:?}"))
+    }
+}
+
+/// Round-trip through RON text; `ron` has no direct value serializer.
+fn to_value<T: Serialize>(component: &T) -> Result<Value> {
+    Ok(ron::from_str(&ron::to_string(component)?)?)
+}
+
+enum Format {
+    Ron,
+    Json,
+}
+
+impl Format {
+    fn of(path: &Path) -> Result<Self> {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("ron")  => Ok(Format::Ron),
+            Some("json") => Ok(Format::Json),
+            _ => bail!("unknown scene format for {path:?}, expected .ron or .json"),
+        }
+    }
+}
+
+struct ComponentEntry {
+    name: &'static str,
+    save: fn(&World, Entity) -> Option<Result<Value>>,
+    load: fn(&mut World, Entity, Value) -> Result<()>,
+}
+
+/// Components that take part in scene (de)serialization, keyed by name.
+pub struct SceneRegistry {
+    entries: Vec<ComponentEntry>,
+}
+
+impl Default for SceneRegistry {
+    /// Registry with the components defined in this crate.
+    fn default() -> Self {
+        let mut registry = Self { entries: Vec::new() };
+        registry
+            .register::<Name>("Name")
+            .register::<Transform>("Transform")
+            .register::<ModelPath>("ModelPath")
+            .register::<Tag>("Tag");
+        registry
+    }
+}
+
+impl SceneRegistry {
+    pub fn register<T>(&mut self, name: &'static str) -> &mut Self
+    where
+        T: Component + Serialize + DeserializeOwned,
+    {
+        assert!(
+            self.entries.iter().all(|e| e.name != name),
+            "scene component `{name}` registered twice"
+        );
+        self.entries.push(ComponentEntry {
+            name,
+            save: |world, entity| {
+                let component = world.get::<&T>(entity).ok()?;
+                Some(to_value(&*component))
+            },
+            load: |world, entity, value| {
+                let component: T = value.into_rust()?;
+                world.insert_one(entity, component)?;
+                Ok(())
+            },
+        });
+        self
+    }
+
+    /// Snapshot every entity that has at least one registered component.
+    pub fn save(&self, world: &World) -> Result<Scene> {
+        let mut scene = Scene::default();
+        for entity_ref in world.iter() {
+            let entity = entity_ref.entity();
+            let mut out = SceneEntity::default();
+            for entry in &self.entries {
+                if let Some(value) = (entry.save)(world, entity) {
+                    let value = value.with_context(|| format!("serializing {}", entry.name))?;
+                    out.components.insert(entry.name.to_owned(), value);
+                }
+            }
+            if !out.components.is_empty() {
+                scene.entities.push(out);
+            }
+        }
+        Ok(scene)
+    }
+
+    /// Spawn the entities of `scene` into `world`, returning them in scene
+    /// order. Unknown component names are an error, in which case nothing is
+    /// left behind in `world`.
+    pub fn load(&self, world: &mut World, scene: &Scene) -> Result<Vec<Entity>> {
+        let mut spawned = Vec::with_capacity(scene.entities.len());
+        let result = scene.entities.iter().try_for_each(|data| {
+            let entity = world.spawn(());
+            spawned.push(entity);
+            data.components.iter().try_for_each(|(name, value)| {
+                let entry = self
+                    .entries
+                    .iter()
+                    .find(|e| e.name == name)
+                    .with_context(|| format!("unregistered scene component `{name}`"))?;
+                (entry.load)(world, entity, value.clone())
+                    .with_context(|| format!("deserializing {name}"))
+            })
+        });
+
+        if let Err(err) = result {
+            for entity in spawned {
+                let _ = world.despawn(entity);
+            }
+            return Err(err);
+        }
+        Ok(spawned)
+    }
+}
+
+impl App {
+    /// Make `T` part of saved scenes under `name`.
+    pub fn register_scene_component<T>(&mut self, name: &'static str) -> &mut Self
+    where
+        T: Component + Serialize + DeserializeOwned,
+    {
+        self.resources.init::<SceneRegistry>();
+        self.resources.fetch_mut::<SceneRegistry>().register::<T>(name);
+        self
+    }
+}
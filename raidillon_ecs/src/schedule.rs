@@ -0,0 +1,235 @@
+//! Stage based system scheduler.
+//!
+//! Systems are plain functions over the [`World`] plus a resource context,
+//! [`Resources`] unless the caller picks something else. Each system lives in
+//! one [`Stage`] and may declare explicit `before`/`after` ordering
+//! constraints against other systems of the same stage by name. Systems
+//! without constraints keep registration order.
+
+use std::collections::HashMap;
+
+use hecs::World;
+use raidillon_core::profile_scope;
+
+use crate::Resources;
+
+/// Fixed set of stages a frame is split into, executed in declaration order.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Stage {
+    PreUpdate,
+    /// Runs zero or more times per frame at [`Schedule::fixed_timestep`].
+    FixedUpdate,
+    Update,
+    PostUpdate,
+    /// Runs once per redraw, right before the world is drawn.
+    Render,
+}
+
+impl Stage {
+    pub const ALL: [Stage; 5] = [
+        Stage::PreUpdate,
+        Stage::FixedUpdate,
+        Stage::Update,
+        Stage::PostUpdate,
+        Stage::Render,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Stage::PreUpdate   => "PreUpdate",
+            Stage::FixedUpdate => "FixedUpdate",
+            Stage::Update      => "Update",
+            Stage::PostUpdate  => "PostUpdate",
+            Stage::Render      => "Render",
+        }
+    }
+}
+
+pub type BoxedSystem<R = Resources> = Box<dyn FnMut(&mut World, &mut R)>;
+
+struct SystemEntry<R> {
+    name:   &'static str,
+    run:    BoxedSystem<R>,
+    before: Vec<&'static str>,
+    after:  Vec<&'static str>,
+}
+
+struct StageSystems<R> {
+    systems: Vec<SystemEntry<R>>,
+    /// Cached execution order, invalidated whenever a system is added.
+    order:   Option<Vec<usize>>,
+}
+
+impl<R> Default for StageSystems<R> {
+    fn default() -> Self {
+        Self { systems: Vec::new(), order: None }
+    }
+}
+
+impl<R> StageSystems<R> {
+    /// Topologically sort the systems of this stage. Ties are broken by
+    /// registration order so unconstrained systems run in the order they were
+    /// added. Constraints naming unknown systems are ignored.
+    fn sorted(&self, stage: Stage) -> Vec<usize> {
+        let n = self.systems.len();
+        let index: HashMap<&str, usize> = self
+            .systems
+            .iter()
+            .enumerate()
+            .map(|(i, s)| (s.name, i))
+            .collect();
+
+        let mut edges: Vec<Vec<usize>> = vec![Vec::new(); n];
+        let mut in_degree = vec![0usize; n];
+        for (i, sys) in self.systems.iter().enumerate() {
+            for other in &sys.before {
+                if let Some(&j) = index.get(other) {
+                    edges[i].push(j);
+                    in_degree[j] += 1;
+                }
+            }
+            for other in &sys.after {
+                if let Some(&j) = index.get(other) {
+                    edges[j].push(i);
+                    in_degree[i] += 1;
+                }
+            }
+        }
+
+        let mut order = Vec::with_capacity(n);
+        let mut done = vec![false; n];
+        while order.len() < n {
+            let next = (0..n).find(|&i| !done[i] && in_degree[i] == 0);
+            let Some(i) = next else {
+                let cycle: Vec<&str> = (0..n)
+                    .filter(|&i| !done[i])
+                    .map(|i| self.systems[i].name)
+                    .collect();
+                panic!("system ordering cycle in {stage:?}: {cycle:?}");
+            };
+            done[i] = true;
+            order.push(i);
+            for &j in &edges[i] {
+                in_degree[j] -= 1;
+            }
+        }
+        order
+    }
+}
+
+/// Collection of systems grouped by [`Stage`].
+pub struct Schedule<R = Resources> {
+    stages:          HashMap<Stage, StageSystems<R>>,
+    fixed_timestep:  f32,
+    max_fixed_steps: u32,
+    accumulator:     f32,
+}
+
+impl<R> Default for Schedule<R> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<R> Schedule<R> {
+    pub fn new() -> Self {
+        Self {
+            stages:          HashMap::new(),
+            fixed_timestep:  1.0 / 60.0,
+            max_fixed_steps: 8,
+            accumulator:     0.0,
+        }
+    }
+
+    /// Register `system` under `name` in `stage`. The returned handle can be
+    /// used to add ordering constraints.
+    pub fn add_system<F>(&mut self, stage: Stage, name: &'static str, system: F) -> SystemConfig<'_, R>
+    where
+        F: FnMut(&mut World, &mut R) + 'static,
+    {
+        let systems = self.stages.entry(stage).or_default();
+        assert!(
+            systems.systems.iter().all(|s| s.name != name),
+            "system `{name}` registered twice in {stage:?}"
+        );
+        systems.order = None;
+        systems.systems.push(SystemEntry {
+            name,
+            run:    Box::new(system),
+            before: Vec::new(),
+            after:  Vec::new(),
+        });
+        SystemConfig { stage: systems }
+    }
+
+    pub fn fixed_timestep(&self) -> f32 {
+        self.fixed_timestep
+    }
+
+    pub fn set_fixed_timestep(&mut self, seconds: f32) {
+        self.fixed_timestep = seconds;
+    }
+
+    /// Upper bound on fixed steps per frame so a long hitch does not snowball.
+    pub fn set_max_fixed_steps(&mut self, steps: u32) {
+        self.max_fixed_steps = steps;
+    }
+
+    /// Run every system registered in `stage` once, in dependency order.
+    pub fn run_stage(&mut self, stage: Stage, world: &mut World, resources: &mut R) {
+        let Some(systems) = self.stages.get_mut(&stage) else {
+            return;
+        };
+        if systems.order.is_none() {
+            systems.order = Some(systems.sorted(stage));
+        }
+        let order = systems.order.as_ref().unwrap();
+        profile_scope!(stage.name());
+        for &i in order {
+            let system = &mut systems.systems[i];
+            profile_scope!(system.name);
+            (system.run)(world, resources);
+        }
+    }
+
+    /// Advance one frame of `dt` seconds: PreUpdate, as many FixedUpdate steps
+    /// as have accumulated, Update and PostUpdate. [`Stage::Render`] is left to
+    /// the caller since it has to line up with the actual redraw.
+    pub fn run(&mut self, world: &mut World, resources: &mut R, dt: f32) {
+        self.run_stage(Stage::PreUpdate, world, resources);
+
+        self.accumulator += dt;
+        let mut steps = 0;
+        while self.accumulator >= self.fixed_timestep {
+            if steps == self.max_fixed_steps {
+                self.accumulator = 0.0;
+                break;
+            }
+            self.run_stage(Stage::FixedUpdate, world, resources);
+            self.accumulator -= self.fixed_timestep;
+            steps += 1;
+        }
+
+        self.run_stage(Stage::Update, world, resources);
+        self.run_stage(Stage::PostUpdate, world, resources);
+    }
+}
+
+/// Handle returned by [`Schedule::add_system`] to declare ordering.
+pub struct SystemConfig<'a, R> {
+    stage: &'a mut StageSystems<R>,
+}
+
+impl<R> SystemConfig<'_, R> {
+    /// Run this system before the system called `other`.
+    pub fn before(self, other: &'static str) -> Self {
+        self.stage.systems.last_mut().unwrap().before.push(other);
+        self
+    }
+
+    /// Run this system after the system called `other`.
+    pub fn after(self, other: &'static str) -> Self {
+        self.stage.systems.last_mut().unwrap().after.push(other);
+        self
+    }
+}
@@ -1,167 +1,196 @@
-use anyhow::Result;
-use glam::{Quat, Vec3, EulerRot};
-use raidillon_core::Time;
-use raidillon_ecs::Transform;
-use raidillon_render::{Camera, ECSRenderer, init_render_window, DisplayHandle};
-use raidillon_ui::Gui;
-use raidillon_input::{Input, FPSCameraController};
-use winit::keyboard::KeyCode;
-use winit::window::CursorGrabMode;
+use std::path::PathBuf;
+
+use anyhow::{bail, Result};
+use glam::Vec3;
+use hecs::World;
+use imgui::{Condition, Ui};
+use raidillon_app::WinitPlugin;
+use raidillon_audio::{AudioEngine, AudioPlugin};
+use raidillon_core::Ray;
+use raidillon_ecs::{App, Camera, Name, Plugin, Resources, Scene, SceneRegistry, Stage};
+use raidillon_physics::{raycast, PhysicsPlugin, ALL_LAYERS};
+use raidillon_render::RenderPlugin;
+use raidillon_ui::{
+    AudioSettingsPlugin, ConsoleAppExt, ConsolePlugin, EditorSettings, EditorSettingsPlugin, GizmoPlugin, GuiConfig,
+    ProfilerPlugin, Selection, UiAppExt, UiPlugin, ViewportPlugin,
+};
+use raidillon_input::{
+    AxisBinding, Binding, FPSCameraController, GamepadAxis, GamepadButton, GamepadPlugin, Input, InputPlugin,
+    ResponseCurve,
+};
 use winit::event::MouseButton;
+use winit::keyboard::KeyCode;
+
+mod systems;
 
 #[derive(Copy, Clone, Eq, PartialEq, Hash)]
 enum Action {
-    MoveForward,
-    MoveBackward,
-    MoveLeft,
-    MoveRight,
+    Move,
+    Look,
+    Turn,
+    Jump,
+    Crouch,
+    /// Held to grab the cursor for mouse look.
+    Aim,
 }
 
+const SCENE_PATH: &str = "resources/scenes/main.ron";
+const AUTOEXEC_PATH: &str = "resources/autoexec.cfg";
+const PHYSICS_CONFIG_PATH: &str = "resources/physics.ron";
+const MUSIC_PATH: &str = "resources/sounds/ambient.wav";
+/// Seconds music tracks take to crossfade.
+const MUSIC_FADE: f32 = 2.0;
+/// How far the "Looking at" readout reaches.
+const LOOK_DISTANCE: f32 = 20.0;
+
+/// Set by the `noclip` console command: the camera flies through everything
+/// instead of walking.
+#[derive(Default)]
+struct Noclip(bool);
+
 fn main() -> Result<()> {
-    let event_loop = winit::event_loop::EventLoop::builder()
-        .build()
-        .expect("create event-loop");
-
-    let (window, _display): (winit::window::Window, DisplayHandle) = init_render_window(&event_loop, "raidillon", (1280, 720))?;
-
-    // Create ECS renderer which internally owns both the world and the renderer
-    let mut ecsr = ECSRenderer::from_display_handle(&_display)?;
-
-    // Dear ImGui integration
-    let mut gui = Gui::new(&_display, &window)?;
-
-    let mut input = Input::<Action>::new();
-    input.map_key(KeyCode::KeyW, Action::MoveForward);
-    input.map_key(KeyCode::KeyS, Action::MoveBackward);
-    input.map_key(KeyCode::KeyA, Action::MoveLeft);
-    input.map_key(KeyCode::KeyD, Action::MoveRight);
-
-    let mut camera_controller = FPSCameraController::new(Vec3::new(0.0, 0.0, 2.0));
-
-    let mut right_mouse_held = false;
-
-    let mut time = Time::new();
-
-    let object_ent = ecsr.load_mesh_from_gltf("resources/models/tree.gltf", Transform {
-        translation: Vec3::new(0.0, -2.5, -5.0),
-        rotation:    Quat::IDENTITY,
-        scale:       Vec3::new(0.01, 0.01, 0.01),
-    })?;
-
-    let ground_ent = ecsr.load_mesh_from_gltf("resources/models/plane.gltf", Transform {
-        translation: Vec3::new(0.0, -1.5, 0.0),
-        rotation:    Quat::IDENTITY,
-        scale:       Vec3::new(1.0, 1.0, 1.0),
-    })?;
-
-
-    let camera_ent = {
-        let (w, h): (u32, u32) = window.inner_size().into();
-        ecsr.world.spawn((Camera {
-            eye:    Vec3::new(0.0, 0.0, 2.0),
-            center: Vec3::ZERO,
-            up:     Vec3::Y,
-            fovy:   60_f32.to_radians(),
-            aspect: w as f32 / h as f32,
-            znear:  0.1,
-            zfar:   100.0,
-        },))
-    };
-
-    event_loop
-        .run(move |event, el| {
-            use winit::event::{Event, WindowEvent};
-
-            gui.handle_event(&window, &event);
-
-            input.handle_event(&event);
-
-            match event {
-                Event::WindowEvent { event, .. } => match event {
-                    WindowEvent::CloseRequested => el.exit(),
-                    WindowEvent::Resized(sz) => {
-                        ecsr.world.query_one_mut::<&mut Camera>(camera_ent).map(|mut cam| {
-                            cam.aspect = sz.width as f32 / sz.height as f32;
-                        });
-                    }
-                    WindowEvent::MouseInput { state, button, .. } => {
-                        if button == MouseButton::Right {
-                            match state {
-                                winit::event::ElementState::Pressed => {
-                                    if window
-                                        .set_cursor_grab(CursorGrabMode::Confined)
-                                        .or_else(|_| window.set_cursor_grab(CursorGrabMode::Locked))
-                                        .is_ok()
-                                    {
-                                        window.set_cursor_visible(false);
-                                        right_mouse_held = true;
-                                    }
-                                }
-                                winit::event::ElementState::Released => {
-                                    let _ = window.set_cursor_grab(CursorGrabMode::None);
-                                    window.set_cursor_visible(true);
-                                    right_mouse_held = false;
-                                }
-                            }
-                        }
-                    }
-                    WindowEvent::RedrawRequested => {
-                        gui.render_world(&mut ecsr, &window, |ui, ecsr| {
-                            if let Ok(mut tr) = ecsr.world.query_one_mut::<&mut Transform>(object_ent) {
-                                ui.text("Hold right click to control the camera");
-                                ui.text("WASD to move");
-
-                                // Translation controls
-                                let mut translation = [tr.translation.x, tr.translation.y, tr.translation.z];
-                                if ui.input_float3("Translation", &mut translation).build() {
-                                    tr.translation = Vec3::from(translation);
-                                }
-
-                                // Scale controls
-                                let mut scale = [tr.scale.x, tr.scale.y, tr.scale.z];
-                                if ui.input_float3("Scale", &mut scale).build() {
-                                    tr.scale = Vec3::from(scale);
-                                }
-
-                                // Rotation controls
-                                let (yaw, pitch, roll) = tr.rotation.to_euler(EulerRot::YXZ);
-                                let mut rotation_deg = [yaw.to_degrees(), pitch.to_degrees(), roll.to_degrees()];
-                                if ui.input_float3("Rotation (deg)", &mut rotation_deg).build() {
-                                    let yaw   = rotation_deg[0].to_radians();
-                                    let pitch = rotation_deg[1].to_radians();
-                                    let roll  = rotation_deg[2].to_radians();
-                                    tr.rotation = Quat::from_euler(EulerRot::YXZ, yaw, pitch, roll);
-                                }
-                            }
-                        });
+    let mut app = App::new();
+    app.add_plugin(WinitPlugin::new("raidillon", (1280, 720)))
+        .add_plugin(RenderPlugin)
+        .add_plugin(PhysicsPlugin::with_config(PHYSICS_CONFIG_PATH))
+        .add_plugin(AudioPlugin::default())
+        .add_plugin(GamepadPlugin)
+        .add_plugin(InputPlugin::<Action>::default())
+        .add_plugin(UiPlugin)
+        .add_plugin(EditorSettingsPlugin::new("raidillon"))
+        .add_plugin(ViewportPlugin)
+        .add_plugin(GizmoPlugin)
+        .add_plugin(ProfilerPlugin)
+        .add_plugin(AudioSettingsPlugin)
+        .add_plugin(ConsolePlugin::with_autoexec(AUTOEXEC_PATH));
+
+    let scene_path = app
+        .resources
+        .fetch::<EditorSettings>()
+        .last_scene
+        .clone()
+        .filter(|path| path.exists())
+        .unwrap_or_else(|| SCENE_PATH.into());
+    let scene = Scene::load_from_file(&scene_path)?;
+
+    app.add_plugin(GamePlugin { scene, scene_path }).run()
+}
+
+struct GamePlugin {
+    scene:      Scene,
+    scene_path: PathBuf,
+}
+
+impl Plugin for GamePlugin {
+    fn build(&self, app: &mut App) {
+        let mut input = Input::<Action>::new();
+        let key = Binding::Key;
+        input.map_analog(
+            AxisBinding::composite(key(KeyCode::KeyW), key(KeyCode::KeyS), key(KeyCode::KeyA), key(KeyCode::KeyD))
+                .normalized(),
+            Action::Move,
+        );
+        input.map_analog(AxisBinding::stick(GamepadAxis::LeftStickX, GamepadAxis::LeftStickY), Action::Move);
+        input.map_analog(AxisBinding::mouse().invert_y(), Action::Look);
+        input.map_analog(AxisBinding::stick(GamepadAxis::RightStickX, GamepadAxis::RightStickY), Action::Turn);
+        input.map_key(KeyCode::Space, Action::Jump);
+        input.map_key(KeyCode::KeyC, Action::Crouch);
+        input.map_mouse(MouseButton::Right, Action::Aim);
+        input.map_button(GamepadButton::South, Action::Jump);
+        input.map_button(GamepadButton::East, Action::Crouch);
+        // Slow turns near the center for aiming.
+        input.gamepad_settings_mut().right_stick.curve = ResponseCurve::Quadratic;
+
+        let mut controller = FPSCameraController::new(Vec3::new(0.0, 0.0, 2.0));
+        {
+            let mut settings = app.resources.fetch_mut::<EditorSettings>();
+            controller.speed = settings.camera_speed;
+            controller.sensitivity = settings.camera_sensitivity;
+            settings.last_scene = Some(self.scene_path.clone());
+        }
+        app.resources.fetch_mut::<GuiConfig>().docking = true;
+
+        app.insert_resource(input)
+            .insert_resource(controller)
+            .init_resource::<Noclip>()
+            .add_console_command("noclip", "toggle camera collision", |_, _, res| {
+                let mut noclip = res.fetch_mut::<Noclip>();
+                noclip.0 = !noclip.0;
+                Ok(format!("noclip {}", if noclip.0 { "on" } else { "off" }))
+            })
+            .add_console_command("music", "crossfade to a music file, or stop the music", |args, _, res| {
+                let mut engine = res.fetch_mut::<AudioEngine>();
+                match args {
+                    [] => {
+                        engine.stop_music(MUSIC_FADE);
+                        Ok("music stopped".to_owned())
                     }
-                    _ => {}
-                },
-                Event::AboutToWait => {
-                    time.tick();
-
-                    {
-                        let dt = time.delta_seconds();
-                        camera_controller.update(
-                            &input,
-                            dt,
-                            right_mouse_held,
-                            (Action::MoveForward, Action::MoveBackward, Action::MoveLeft, Action::MoveRight),
-                        );
-
-                        if let Ok(mut cam) = ecsr.world.query_one_mut::<&mut Camera>(camera_ent) {
-                            cam.eye    = camera_controller.position;
-                            cam.center = camera_controller.position + camera_controller.front();
-                        }
+                    [path] => {
+                        engine.play_music(path, MUSIC_FADE)?;
+                        Ok(format!("playing {path}"))
                     }
+                    _ => bail!("usage: music [file]"),
+                }
+            })
+            .add_startup_system(setup(self.scene.clone()))
+            .add_system(Stage::Update, "cursor_grab", systems::cursor_grab)
+            .add_system(Stage::Update, "camera_controller", systems::camera_controller)
+            .add_system(Stage::Update, "kill_zone", systems::kill_zone())
+            .add_system(Stage::PostUpdate, "store_camera_settings", systems::store_camera_settings)
+            .add_ui_system(game_panel);
+    }
+}
 
-                    input.end_frame();
+fn setup(scene: Scene) -> impl FnMut(&mut World, &mut Resources) {
+    move |world, res| {
+        if let Err(err) = res.fetch::<SceneRegistry>().load(world, &scene) {
+            eprintln!("[game] {err:#}");
+        }
+
+        let tree = world
+            .query::<&Name>()
+            .iter()
+            .find(|(_, name)| name.0 == "tree")
+            .map(|(e, _)| e);
+        res.fetch_mut::<Selection>().0 = tree;
+
+        if let Err(err) = res.fetch_mut::<AudioEngine>().play_music(MUSIC_PATH, MUSIC_FADE) {
+            eprintln!("[game] {err:#}");
+        }
+    }
+}
 
-                    gui.prepare_frame(&window);
-                    window.request_redraw();
+fn game_panel(ui: &Ui, world: &mut World, res: &mut Resources) {
+    ui.window("Game")
+        .position([1010.0, 10.0], Condition::FirstUseEver)
+        .size([280.0, 140.0], Condition::FirstUseEver)
+        .build(|| {
+            ui.text("Hold right click to control the camera");
+            ui.text("WASD to move, space to jump, C to crouch");
+            ui.text("Gamepad: sticks to move and look, A to jump");
+            ui.text("F3 toggles the profiler, ` the console");
+            match look_target(world, res) {
+                Some((name, distance)) => ui.text(format!("Looking at {name} ({distance:.1} m)")),
+                None => ui.text_disabled("Looking at nothing"),
+            }
+            if ui.button("Save scene") {
+                let path = res.fetch::<EditorSettings>().last_scene.clone().unwrap_or_else(|| SCENE_PATH.into());
+                let saved = res
+                    .fetch::<SceneRegistry>()
+                    .save(world)
+                    .and_then(|scene| scene.save_to_file(path));
+                if let Err(err) = saved {
+                    eprintln!("[game] {err:#}");
                 }
-                _ => {}
             }
-        })
-        .map_err(Into::into)
+        });
+}
+
+/// Name of the entity in the middle of the view and how far away it is.
+fn look_target(world: &World, res: &Resources) -> Option<(String, f32)> {
+    let camera = world.query::<&Camera>().iter().next().map(|(_, c)| *c)?;
+    let ray = Ray::new(camera.eye, camera.center - camera.eye);
+    let hit = raycast(world, res, &ray, LOOK_DISTANCE, ALL_LAYERS)?;
+    let name = world.get::<&Name>(hit.entity).map(|n| n.0.clone()).unwrap_or_else(|_| format!("{:?}", hit.entity));
+    Some((name, hit.distance))
 }
@@ -0,0 +1,89 @@
+use glam::Vec3;
+use hecs::World;
+use raidillon_core::Time;
+use raidillon_ecs::{Camera, EventReader, Events, Name, Resources, Transform};
+use raidillon_input::{CameraActions, CursorGrab, FPSCameraController, Input};
+use raidillon_physics::{CharacterController, TriggerEnter};
+use raidillon_ui::EditorSettings;
+
+use crate::{Action, Noclip};
+
+/// Grab the cursor for mouse look while aiming.
+pub fn cursor_grab(_world: &mut World, res: &mut Resources) {
+    let aiming = res.fetch::<Input<Action>>().action_held(Action::Aim);
+    res.fetch_mut::<CursorGrab>().requested = aiming;
+}
+
+/// Walk the camera entity's character from WASD or the left stick with mouse
+/// or right stick look, or fly the camera with noclip on.
+pub fn camera_controller(world: &mut World, res: &mut Resources) {
+    let input = res.fetch::<Input<Action>>();
+    let mut controller = res.fetch_mut::<FPSCameraController>();
+    let noclip = res.fetch::<Noclip>().0;
+    let grabbed = res.fetch::<CursorGrab>().grabbed;
+    let dt = res.fetch::<Time>().delta_seconds();
+    let actions = CameraActions { movement: Action::Move, look: Action::Look, turn: Action::Turn };
+
+    let Some((_, (cam, transform, character))) = world
+        .query_mut::<(&mut Camera, Option<&mut Transform>, Option<&mut CharacterController>)>()
+        .into_iter()
+        .next()
+    else {
+        return;
+    };
+
+    match (transform, character) {
+        (Some(transform), Some(character)) if !noclip => {
+            controller.look(&input, dt, grabbed, actions);
+            character.enabled = true;
+            character.movement = controller.walk_velocity(&input, actions.movement);
+            character.jump |= input.action_pressed(Action::Jump);
+            character.crouch = input.action_held(Action::Crouch);
+            controller.position = transform.translation + Vec3::Y * character.eye_height();
+        }
+        (transform, character) => {
+            controller.update(&input, dt, grabbed, actions);
+            if let (Some(transform), Some(character)) = (transform, character) {
+                character.enabled = false;
+                transform.translation = controller.position - Vec3::Y * character.eye_height();
+            }
+        }
+    }
+
+    cam.eye    = controller.position;
+    cam.center = controller.position + controller.front();
+}
+
+/// Put anything entering the trigger named `kill_zone` back at the entity
+/// named `spawn`.
+pub fn kill_zone() -> impl FnMut(&mut World, &mut Resources) {
+    let mut reader = EventReader::<TriggerEnter>::default();
+    move |world, res| {
+        let named = |world: &World, wanted: &str| {
+            world.query::<&Name>().iter().find(|(_, name)| name.0 == wanted).map(|(e, _)| e)
+        };
+        let Some(spawn) = named(world, "spawn").and_then(|e| world.get::<&Transform>(e).ok().map(|t| t.translation))
+        else {
+            return;
+        };
+        for event in reader.read(&res.fetch::<Events<TriggerEnter>>()) {
+            if named(world, "kill_zone") != Some(event.trigger) {
+                continue;
+            }
+            if let Ok(mut transform) = world.get::<&mut Transform>(event.entity) {
+                transform.translation = spawn;
+            }
+            if let Ok(mut character) = world.get::<&mut CharacterController>(event.entity) {
+                character.vertical_speed = 0.0;
+            }
+        }
+    }
+}
+
+/// Keep the persisted editor settings in line with the camera controller.
+pub fn store_camera_settings(_world: &mut World, res: &mut Resources) {
+    let controller = res.fetch::<FPSCameraController>();
+    let mut settings = res.fetch_mut::<EditorSettings>();
+    settings.camera_speed = controller.speed;
+    settings.camera_sensitivity = controller.sensitivity;
+}
@@ -0,0 +1,105 @@
+//! Analog actions read as a value or a 2D vector, e.g. movement from WASD
+//! or a stick and looking around from mouse motion.
+
+use glam::Vec2;
+
+use crate::{Binding, GamepadAxis};
+
+/// Where one component of an [`AxisBinding`] comes from.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum AxisInput {
+    /// -1 while `negative` is held, 1 while `positive` is, 0 for both or
+    /// neither. Axis bindings count with their deflection.
+    Composite { negative: Binding, positive: Binding },
+    /// After the gamepad's dead zone and response curve.
+    Gamepad(GamepadAxis),
+    /// Mouse motion this frame, right positive.
+    MouseX,
+    /// Mouse motion this frame, down positive.
+    MouseY,
+    /// Wheel scrolled this frame in lines, right positive.
+    ScrollX,
+    /// Wheel scrolled this frame in lines, away from the user positive.
+    ScrollY,
+}
+
+/// Binds an analog action to one input, or to two for a 2D action.
+/// Values are inverted, then scaled, then normalized.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct AxisBinding {
+    pub x:         AxisInput,
+    /// `None` for 1D actions, which read 0 on y.
+    pub y:         Option<AxisInput>,
+    pub scale:     Vec2,
+    pub invert_x:  bool,
+    pub invert_y:  bool,
+    /// Clamp the result to length 1, so e.g. diagonal WASD isn't faster.
+    pub normalize: bool,
+}
+
+impl AxisBinding {
+    pub fn new(x: AxisInput) -> Self {
+        Self { x, y: None, scale: Vec2::ONE, invert_x: false, invert_y: false, normalize: false }
+    }
+
+    pub fn new_2d(x: AxisInput, y: AxisInput) -> Self {
+        Self { y: Some(y), ..Self::new(x) }
+    }
+
+    /// Four bindings as a 2D direction, up positive, e.g. WASD.
+    pub fn composite(up: Binding, down: Binding, left: Binding, right: Binding) -> Self {
+        Self::new_2d(
+            AxisInput::Composite { negative: left, positive: right },
+            AxisInput::Composite { negative: down, positive: up },
+        )
+    }
+
+    /// A gamepad stick, up positive.
+    pub fn stick(x: GamepadAxis, y: GamepadAxis) -> Self {
+        Self::new_2d(AxisInput::Gamepad(x), AxisInput::Gamepad(y))
+    }
+
+    /// Mouse motion this frame, down positive.
+    pub fn mouse() -> Self {
+        Self::new_2d(AxisInput::MouseX, AxisInput::MouseY)
+    }
+
+    /// Scroll wheel this frame in lines.
+    pub fn scroll() -> Self {
+        Self::new_2d(AxisInput::ScrollX, AxisInput::ScrollY)
+    }
+
+    pub fn scale(self, scale: f32) -> Self {
+        Self { scale: Vec2::splat(scale), ..self }
+    }
+
+    pub fn scale_xy(self, scale: Vec2) -> Self {
+        Self { scale, ..self }
+    }
+
+    pub fn invert_x(self) -> Self {
+        Self { invert_x: !self.invert_x, ..self }
+    }
+
+    pub fn invert_y(self) -> Self {
+        Self { invert_y: !self.invert_y, ..self }
+    }
+
+    pub fn normalized(self) -> Self {
+        Self { normalize: true, ..self }
+    }
+
+    /// Apply inversion, scale and normalization to the raw input values.
+    pub(crate) fn process(&self, raw: Vec2) -> Vec2 {
+        let sign = Vec2::new(
+            if self.invert_x { -1.0 } else { 1.0 },
+            if self.invert_y { -1.0 } else { 1.0 },
+        );
+        let value = raw * sign * self.scale;
+        if self.normalize {
+            value.clamp_length_max(1.0)
+        } else {
+            value
+        }
+    }
+}
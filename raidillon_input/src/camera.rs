@@ -3,6 +3,18 @@ use std::hash::Hash;
 
 use super::Input;
 
+/// Analog actions driving an [`FPSCameraController`]. All are 2D with right
+/// and up (or forward) positive.
+#[derive(Debug, Clone, Copy)]
+pub struct CameraActions<A> {
+    /// Walking or flying direction, clamped to length 1.
+    pub movement: A,
+    /// Per-frame rotation, e.g. mouse motion.
+    pub look:     A,
+    /// Turn rate from -1 to 1, e.g. a gamepad stick.
+    pub turn:     A,
+}
+
 #[derive(Debug, Clone)]
 pub struct FPSCameraController {
     pub position: Vec3,
@@ -10,6 +22,9 @@ pub struct FPSCameraController {
     pitch: f32,
     pub speed: f32,
     pub sensitivity: f32,
+    /// Turn rate with the turn action fully deflected, in degrees per
+    /// second.
+    pub stick_speed: f32,
 }
 
 impl FPSCameraController {
@@ -20,44 +35,57 @@ impl FPSCameraController {
             pitch: 0.0,
             speed: 3.0,
             sensitivity: 0.1,
+            stick_speed: 120.0,
         }
     }
 
+    /// Look plus free flight in the look direction, e.g. for noclip.
     pub fn update<A>(&mut self,
                      input: &Input<A>,
                      dt: f32,
                      mouse_enabled: bool,
-                     actions: (A, A, A, A))
+                     actions: CameraActions<A>)
     where
         A: Copy + Eq + Hash,
     {
-        let (forward, backward, left, right) = actions;
-
-        // Mouse look
-        if mouse_enabled {
-            let (dx, dy) = input.mouse_delta();
-            self.yaw   += dx as f32 * self.sensitivity;
-            self.pitch -= dy as f32 * self.sensitivity;
-            self.pitch = self.pitch.clamp(-89.0, 89.0);
-        }
+        self.look(input, dt, mouse_enabled, actions);
 
         // Movement
         let front = self.front();
         let right_vec = front.cross(Vec3::Y).normalize();
-        let frame_speed = self.speed * dt;
+        let movement = input.axis_2d(actions.movement).clamp_length_max(1.0);
+        self.position += (front * movement.y + right_vec * movement.x) * self.speed * dt;
+    }
 
-        if input.action_held(forward) {
-            self.position += front * frame_speed;
-        }
-        if input.action_held(backward) {
-            self.position -= front * frame_speed;
-        }
-        if input.action_held(left) {
-            self.position -= right_vec * frame_speed;
-        }
-        if input.action_held(right) {
-            self.position += right_vec * frame_speed;
+    /// Turn the view from the look action, scaled by
+    /// [`sensitivity`](Self::sensitivity) while `mouse_enabled`, and the
+    /// turn action, at up to [`stick_speed`](Self::stick_speed).
+    pub fn look<A>(&mut self, input: &Input<A>, dt: f32, mouse_enabled: bool, actions: CameraActions<A>)
+    where
+        A: Copy + Eq + Hash,
+    {
+        let mut turn = input.axis_2d(actions.turn).clamp_length_max(1.0) * self.stick_speed * dt;
+        if mouse_enabled {
+            turn += input.axis_2d(actions.look) * self.sensitivity;
         }
+        self.yaw   += turn.x;
+        self.pitch += turn.y;
+        self.pitch = self.pitch.clamp(-89.0, 89.0);
+    }
+
+    /// Walking velocity from the move action: on the horizontal plane
+    /// whatever the pitch, at most [`speed`](Self::speed). A partly
+    /// deflected stick walks slower.
+    pub fn walk_velocity<A>(&self, input: &Input<A>, movement: A) -> Vec3
+    where
+        A: Copy + Eq + Hash,
+    {
+        let yaw_rad = self.yaw.to_radians();
+        let front = Vec3::new(yaw_rad.cos(), 0.0, yaw_rad.sin());
+        let right_vec = front.cross(Vec3::Y);
+
+        let movement = input.axis_2d(movement).clamp_length_max(1.0);
+        (front * movement.y + right_vec * movement.x) * self.speed
     }
 
     pub fn front(&self) -> Vec3 {
@@ -0,0 +1,271 @@
+//! Gamepad buttons, sticks and triggers.
+//!
+//! Gamepads reach [`Input`](crate::Input) as [`GamepadEvent`]s. With the
+//! `gilrs` feature, [`GamepadPlugin`] polls the connected gamepads every
+//! frame and sends them; tests or other backends can send their own.
+
+use std::collections::{HashMap, HashSet};
+
+use raidillon_ecs::{App, Plugin};
+
+/// Name of the system sending [`GamepadEvent`]s, which input systems run
+/// after.
+pub const GAMEPAD_SYSTEM: &str = "gamepads";
+
+/// Buttons named by position, e.g. `South` is A on an Xbox pad and Cross on
+/// a PlayStation one.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum GamepadButton {
+    South,
+    East,
+    North,
+    West,
+    LeftBumper,
+    RightBumper,
+    /// Pressed past its threshold; the analog value is
+    /// [`GamepadAxis::LeftTrigger`].
+    LeftTrigger,
+    RightTrigger,
+    Select,
+    Start,
+    Mode,
+    LeftStick,
+    RightStick,
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+}
+
+/// Analog inputs. Sticks go from -1 to 1, up and right positive; triggers
+/// from 0 to 1.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum GamepadAxis {
+    LeftStickX,
+    LeftStickY,
+    RightStickX,
+    RightStickY,
+    LeftTrigger,
+    RightTrigger,
+}
+
+impl GamepadAxis {
+    /// The X and Y axes of the stick this axis belongs to.
+    pub fn stick(self) -> Option<(GamepadAxis, GamepadAxis)> {
+        match self {
+            GamepadAxis::LeftStickX | GamepadAxis::LeftStickY => {
+                Some((GamepadAxis::LeftStickX, GamepadAxis::LeftStickY))
+            }
+            GamepadAxis::RightStickX | GamepadAxis::RightStickY => {
+                Some((GamepadAxis::RightStickX, GamepadAxis::RightStickY))
+            }
+            GamepadAxis::LeftTrigger | GamepadAxis::RightTrigger => None,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum GamepadEventKind {
+    Connected,
+    Disconnected,
+    ButtonPressed(GamepadButton),
+    ButtonReleased(GamepadButton),
+    /// Raw value, before dead zones and response curves.
+    AxisChanged(GamepadAxis, f32),
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct GamepadEvent {
+    /// Which gamepad, stable while it stays connected.
+    pub gamepad: usize,
+    pub kind:    GamepadEventKind,
+}
+
+impl GamepadEvent {
+    /// Whether the event starts new input: a button press or a connection.
+    pub fn is_press(&self) -> bool {
+        matches!(self.kind, GamepadEventKind::ButtonPressed(_) | GamepadEventKind::Connected)
+    }
+}
+
+/// How deflection maps to the value seen by the game, past the dead zone.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ResponseCurve {
+    Linear,
+    /// Finer control near the center.
+    Quadratic,
+    Cubic,
+}
+
+impl ResponseCurve {
+    pub fn apply(self, x: f32) -> f32 {
+        match self {
+            ResponseCurve::Linear => x,
+            ResponseCurve::Quadratic => x * x,
+            ResponseCurve::Cubic => x * x * x,
+        }
+    }
+}
+
+/// Processing of a stick or trigger.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct AxisSettings {
+    /// Deflection below which the input reads 0; the rest is rescaled to
+    /// 0..=1. Sticks use the distance from the center, so the dead zone is
+    /// round.
+    pub dead_zone: f32,
+    pub curve:     ResponseCurve,
+}
+
+impl AxisSettings {
+    /// Processed magnitude of a raw deflection in 0..=1.
+    pub fn apply(&self, magnitude: f32) -> f32 {
+        let live = 1.0 - self.dead_zone;
+        if magnitude <= self.dead_zone || live <= 0.0 {
+            return 0.0;
+        }
+        self.curve.apply(((magnitude - self.dead_zone) / live).min(1.0))
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct GamepadSettings {
+    pub left_stick:  AxisSettings,
+    pub right_stick: AxisSettings,
+    pub triggers:    AxisSettings,
+}
+
+impl Default for GamepadSettings {
+    fn default() -> Self {
+        let stick = AxisSettings { dead_zone: 0.15, curve: ResponseCurve::Linear };
+        Self {
+            left_stick:  stick,
+            right_stick: stick,
+            triggers:    AxisSettings { dead_zone: 0.05, curve: ResponseCurve::Linear },
+        }
+    }
+}
+
+impl GamepadSettings {
+    pub fn for_axis(&self, axis: GamepadAxis) -> &AxisSettings {
+        match axis {
+            GamepadAxis::LeftStickX | GamepadAxis::LeftStickY => &self.left_stick,
+            GamepadAxis::RightStickX | GamepadAxis::RightStickY => &self.right_stick,
+            GamepadAxis::LeftTrigger | GamepadAxis::RightTrigger => &self.triggers,
+        }
+    }
+}
+
+/// Raw state of one gamepad.
+#[derive(Default)]
+pub(crate) struct GamepadState {
+    pub buttons: HashSet<GamepadButton>,
+    pub axes:    HashMap<GamepadAxis, f32>,
+}
+
+impl GamepadState {
+    pub fn raw(&self, axis: GamepadAxis) -> f32 {
+        self.axes.get(&axis).copied().unwrap_or(0.0)
+    }
+
+    /// `axis` after the dead zone and response curve of `settings`.
+    pub fn value(&self, axis: GamepadAxis, settings: &GamepadSettings) -> f32 {
+        let settings = settings.for_axis(axis);
+        let Some((x, y)) = axis.stick() else {
+            return settings.apply(self.raw(axis).abs()) * self.raw(axis).signum();
+        };
+        let (x, y) = (self.raw(x), self.raw(y));
+        let magnitude = x.hypot(y);
+        if magnitude <= 0.0 {
+            return 0.0;
+        }
+        self.raw(axis) / magnitude * settings.apply(magnitude)
+    }
+}
+
+/// Sends [`GamepadEvent`]s from the connected gamepads when built with the
+/// `gilrs` feature.
+pub struct GamepadPlugin;
+
+impl Plugin for GamepadPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<GamepadEvent>();
+        #[cfg(feature = "gilrs")]
+        app.add_system(raidillon_ecs::Stage::PreUpdate, GAMEPAD_SYSTEM, gilrs_backend::poll_gamepads());
+        #[cfg(not(feature = "gilrs"))]
+        eprintln!("[input] built without the `gilrs` feature, gamepads are disabled");
+    }
+}
+
+#[cfg(feature = "gilrs")]
+mod gilrs_backend {
+    use gilrs::{Axis, Button, EventType, Gilrs};
+    use hecs::World;
+    use raidillon_ecs::{Events, Resources};
+
+    use super::{GamepadAxis, GamepadButton, GamepadEvent, GamepadEventKind};
+
+    pub fn poll_gamepads() -> impl FnMut(&mut World, &mut Resources) {
+        let mut gilrs = Gilrs::new()
+            .map_err(|err| eprintln!("[input] gamepads are disabled: {err}"))
+            .ok();
+        move |_world, res| {
+            let Some(gilrs) = &mut gilrs else { return };
+            let mut events = res.fetch_mut::<Events<GamepadEvent>>();
+            while let Some(event) = gilrs.next_event() {
+                if let Some(kind) = translate(event.event) {
+                    events.send(GamepadEvent { gamepad: event.id.into(), kind });
+                }
+            }
+        }
+    }
+
+    fn translate(event: EventType) -> Option<GamepadEventKind> {
+        Some(match event {
+            EventType::Connected => GamepadEventKind::Connected,
+            EventType::Disconnected => GamepadEventKind::Disconnected,
+            EventType::ButtonPressed(button, _) => GamepadEventKind::ButtonPressed(button_from(button)?),
+            EventType::ButtonReleased(button, _) => GamepadEventKind::ButtonReleased(button_from(button)?),
+            EventType::ButtonChanged(Button::LeftTrigger2, value, _) => {
+                GamepadEventKind::AxisChanged(GamepadAxis::LeftTrigger, value)
+            }
+            EventType::ButtonChanged(Button::RightTrigger2, value, _) => {
+                GamepadEventKind::AxisChanged(GamepadAxis::RightTrigger, value)
+            }
+            EventType::AxisChanged(axis, value, _) => {
+                let axis = match axis {
+                    Axis::LeftStickX => GamepadAxis::LeftStickX,
+                    Axis::LeftStickY => GamepadAxis::LeftStickY,
+                    Axis::RightStickX => GamepadAxis::RightStickX,
+                    Axis::RightStickY => GamepadAxis::RightStickY,
+                    _ => return None,
+                };
+                GamepadEventKind::AxisChanged(axis, value)
+            }
+            _ => return None,
+        })
+    }
+
+    fn button_from(button: Button) -> Option<GamepadButton> {
+        Some(match button {
+            Button::South => GamepadButton::South,
+            Button::East => GamepadButton::East,
+            Button::North => GamepadButton::North,
+            Button::West => GamepadButton::West,
+            Button::LeftTrigger => GamepadButton::LeftBumper,
+            Button::RightTrigger => GamepadButton::RightBumper,
+            Button::LeftTrigger2 => GamepadButton::LeftTrigger,
+            Button::RightTrigger2 => GamepadButton::RightTrigger,
+            Button::Select => GamepadButton::Select,
+            Button::Start => GamepadButton::Start,
+            Button::Mode => GamepadButton::Mode,
+            Button::LeftThumb => GamepadButton::LeftStick,
+            Button::RightThumb => GamepadButton::RightStick,
+            Button::DPadUp => GamepadButton::DPadUp,
+            Button::DPadDown => GamepadButton::DPadDown,
+            Button::DPadLeft => GamepadButton::DPadLeft,
+            Button::DPadRight => GamepadButton::DPadRight,
+            _ => return None,
+        })
+    }
+}
@@ -1,21 +1,118 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::hash::Hash;
 
-use winit::event::{DeviceEvent, ElementState, Event, WindowEvent};
-use winit::keyboard::{KeyCode, PhysicalKey};
+use glam::Vec2;
+use winit::event::{DeviceEvent, ElementState, Event, MouseButton, MouseScrollDelta, WindowEvent};
+use winit::keyboard::{KeyCode, ModifiersState, PhysicalKey};
 
+pub mod axis;
 pub mod camera;
-pub use camera::FPSCameraController;
+pub mod gamepad;
+pub mod plugin;
+pub use axis::{AxisBinding, AxisInput};
+pub use camera::{CameraActions, FPSCameraController};
+pub use gamepad::{
+    AxisSettings, GamepadAxis, GamepadButton, GamepadEvent, GamepadEventKind, GamepadPlugin, GamepadSettings,
+    ResponseCurve, GAMEPAD_SYSTEM,
+};
+pub use plugin::InputPlugin;
+
+use gamepad::GamepadState;
+
+/// Raw winit event as republished by the window runner.
+pub type WinitEvent = Event<()>;
+
+/// Whether the cursor is grabbed for mouse look. The window runner grabs or
+/// releases it to follow `requested`, e.g. set while an aim action is held.
+#[derive(Default)]
+pub struct CursorGrab {
+    pub grabbed:   bool,
+    pub requested: bool,
+}
+
+/// Whether the game view has input focus, e.g. because the mouse is over the
+/// editor's viewport panel. While unfocused, inputs registered with
+/// [`InputPlugin`] ignore new key and button presses, mouse motion and
+/// scrolling.
+pub struct InputFocus {
+    pub focused: bool,
+}
+
+impl Default for InputFocus {
+    fn default() -> Self {
+        Self { focused: true }
+    }
+}
+
+/// Half of a gamepad axis.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum AxisDirection {
+    Positive,
+    Negative,
+}
+
+/// Scroll wheel direction; up is away from the user.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum WheelDirection {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+/// A physical input an action can be bound to.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Binding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    /// Pressed and released in the frame the wheel turns, never held.
+    Wheel(WheelDirection),
+    Button(GamepadButton),
+    /// One direction of a stick or a trigger. The action's value follows
+    /// the deflection; it counts as held past [`AXIS_PRESS_THRESHOLD`].
+    Axis(GamepadAxis, AxisDirection),
+}
+
+/// Processed deflection at which an axis binding holds its action.
+pub const AXIS_PRESS_THRESHOLD: f32 = 0.5;
+
+/// Pixels of touchpad scrolling counted as one line of wheel scrolling.
+const PIXELS_PER_LINE: f64 = 20.0;
 
 pub struct Input<A: Copy + Eq + Hash> {
     pressed_keys: HashSet<KeyCode>,
     pressed_once: HashSet<KeyCode>,
+    released_once: HashSet<KeyCode>,
+
+    pressed_mouse: HashSet<MouseButton>,
+    mouse_pressed_once: HashSet<MouseButton>,
+    mouse_released_once: HashSet<MouseButton>,
 
-    keymap: HashMap<KeyCode, A>,
+    keymap: HashMap<Binding, A>,
+    /// Bindings that only fire with exactly these modifiers held.
+    chords: HashMap<(KeyCode, ModifiersState), A>,
+    modifiers: ModifiersState,
     pressed_actions: HashSet<A>,
     pressed_actions_once: HashSet<A>,
+    released_actions_once: HashSet<A>,
+    /// Actions held by axis bindings rather than keys or buttons.
+    axis_actions: HashSet<A>,
+    /// Analog actions; the values of several bindings add up.
+    analog: HashMap<A, Vec<AxisBinding>>,
 
     mouse_delta: (f64, f64),
+    /// Scrolled this frame in lines.
+    scroll_delta: (f64, f64),
+
+    gamepads: BTreeMap<usize, GamepadState>,
+    buttons_pressed_once: HashSet<GamepadButton>,
+    gamepad_settings: GamepadSettings,
+}
+
+impl<A: Copy + Eq + Hash> Default for Input<A> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl<A: Copy + Eq + Hash> Input<A> {
@@ -23,85 +120,373 @@ impl<A: Copy + Eq + Hash> Input<A> {
         Self {
             pressed_keys: HashSet::new(),
             pressed_once: HashSet::new(),
+            released_once: HashSet::new(),
+            pressed_mouse: HashSet::new(),
+            mouse_pressed_once: HashSet::new(),
+            mouse_released_once: HashSet::new(),
             keymap: HashMap::new(),
+            chords: HashMap::new(),
+            modifiers: ModifiersState::empty(),
             pressed_actions: HashSet::new(),
             pressed_actions_once: HashSet::new(),
+            released_actions_once: HashSet::new(),
+            axis_actions: HashSet::new(),
+            analog: HashMap::new(),
             mouse_delta: (0.0, 0.0),
+            scroll_delta: (0.0, 0.0),
+            gamepads: BTreeMap::new(),
+            buttons_pressed_once: HashSet::new(),
+            gamepad_settings: GamepadSettings::default(),
         }
     }
 
+    pub fn map(&mut self, binding: Binding, action: A) {
+        self.keymap.insert(binding, action);
+    }
+
     pub fn map_key(&mut self, key: KeyCode, action: A) {
-        self.keymap.insert(key, action);
+        self.map(Binding::Key(key), action);
+    }
+
+    pub fn map_mouse(&mut self, button: MouseButton, action: A) {
+        self.map(Binding::Mouse(button), action);
+    }
+
+    pub fn map_wheel(&mut self, direction: WheelDirection, action: A) {
+        self.map(Binding::Wheel(direction), action);
+    }
+
+    pub fn map_button(&mut self, button: GamepadButton, action: A) {
+        self.map(Binding::Button(button), action);
+    }
+
+    pub fn map_axis(&mut self, axis: GamepadAxis, direction: AxisDirection, action: A) {
+        self.map(Binding::Axis(axis, direction), action);
+    }
+
+    /// Add an analog binding to `action`, read with [`axis`](Self::axis) or
+    /// [`axis_2d`](Self::axis_2d).
+    pub fn map_analog(&mut self, binding: AxisBinding, action: A) {
+        self.analog.entry(action).or_default().push(binding);
+    }
+
+    /// Bind `key` pressed while exactly `modifiers` are held, e.g.
+    /// Ctrl+Shift+Z. Plain [`map_key`](Self::map_key) bindings ignore
+    /// modifiers.
+    pub fn map_chord(&mut self, modifiers: ModifiersState, key: KeyCode, action: A) {
+        self.chords.insert((key, modifiers), action);
     }
 
     pub fn clear_keymap(&mut self) {
         self.keymap.clear();
+        self.chords.clear();
+        self.analog.clear();
         self.pressed_actions.clear();
         self.pressed_actions_once.clear();
+        self.released_actions_once.clear();
+        self.axis_actions.clear();
     }
 
     pub fn handle_event<T>(&mut self, event: &Event<T>) {
         match event {
-            Event::WindowEvent { event, .. } => match event {
-                WindowEvent::KeyboardInput { event, .. } => {
-                    let key_code = match event.physical_key {
-                        PhysicalKey::Code(code) => code,
-                        _ => return,
-                    };
-
-                    match event.state {
-                        ElementState::Pressed => {
-                            self.pressed_keys.insert(key_code);
-                            self.pressed_once.insert(key_code);
-
-                            if let Some(&action) = self.keymap.get(&key_code) {
-                                self.pressed_actions.insert(action);
-                                self.pressed_actions_once.insert(action);
-                            }
-                        }
-                        ElementState::Released => {
-                            self.pressed_keys.remove(&key_code);
-
-                            if let Some(&action) = self.keymap.get(&key_code) {
-                                self.pressed_actions.remove(&action);
-                            }
-                        }
-                    }
+            Event::WindowEvent { event: WindowEvent::KeyboardInput { event, .. }, .. } => {
+                if let PhysicalKey::Code(key_code) = event.physical_key {
+                    self.handle_key(key_code, event.state);
                 }
-                _ => {}
-            },
-            Event::DeviceEvent { event, .. } => match event {
-                DeviceEvent::MouseMotion { delta } => {
-                    self.mouse_delta.0 += delta.0;
-                    self.mouse_delta.1 += delta.1;
+            }
+            Event::WindowEvent { event: WindowEvent::MouseInput { state, button, .. }, .. } => match state {
+                ElementState::Pressed => {
+                    self.pressed_mouse.insert(*button);
+                    self.mouse_pressed_once.insert(*button);
+                    self.press(Binding::Mouse(*button));
+                }
+                ElementState::Released => {
+                    self.pressed_mouse.remove(button);
+                    self.mouse_released_once.insert(*button);
+                    self.release(Binding::Mouse(*button));
                 }
-                _ => {}
             },
+            Event::WindowEvent { event: WindowEvent::ModifiersChanged(modifiers), .. } => {
+                self.modifiers = modifiers.state();
+            }
+            Event::DeviceEvent { event: DeviceEvent::MouseMotion { delta }, .. } => {
+                self.mouse_delta.0 += delta.0;
+                self.mouse_delta.1 += delta.1;
+            }
+            Event::WindowEvent { event: WindowEvent::MouseWheel { delta, .. }, .. } => {
+                let (x, y) = match *delta {
+                    MouseScrollDelta::LineDelta(x, y) => (x as f64, y as f64),
+                    MouseScrollDelta::PixelDelta(pos) => (pos.x / PIXELS_PER_LINE, pos.y / PIXELS_PER_LINE),
+                };
+                self.scroll_delta.0 += x;
+                self.scroll_delta.1 += y;
+
+                let turned = [
+                    (y > 0.0, WheelDirection::Up),
+                    (y < 0.0, WheelDirection::Down),
+                    (x < 0.0, WheelDirection::Left),
+                    (x > 0.0, WheelDirection::Right),
+                ];
+                for (_, direction) in turned.into_iter().filter(|&(turned, _)| turned) {
+                    if let Some(&action) = self.keymap.get(&Binding::Wheel(direction)) {
+                        self.pressed_actions_once.insert(action);
+                        self.released_actions_once.insert(action);
+                    }
+                }
+            }
             _ => {}
         }
     }
 
+    /// Press or release `key_code`, as a keyboard event would.
+    pub fn handle_key(&mut self, key_code: KeyCode, state: ElementState) {
+        match state {
+            ElementState::Pressed => {
+                self.pressed_keys.insert(key_code);
+                self.pressed_once.insert(key_code);
+
+                if let Some(&action) = self.chords.get(&(key_code, self.modifiers)) {
+                    self.pressed_actions.insert(action);
+                    self.pressed_actions_once.insert(action);
+                }
+                self.press(Binding::Key(key_code));
+            }
+            ElementState::Released => {
+                self.pressed_keys.remove(&key_code);
+                self.released_once.insert(key_code);
+
+                self.release(Binding::Key(key_code));
+                let chorded: Vec<A> = self
+                    .chords
+                    .iter()
+                    .filter(|((key, _), _)| *key == key_code)
+                    .map(|(_, &action)| action)
+                    .collect();
+                for action in chorded {
+                    self.release_action(action);
+                }
+            }
+        }
+    }
+
+    pub fn handle_gamepad_event(&mut self, event: &GamepadEvent) {
+        match event.kind {
+            GamepadEventKind::Connected => {
+                self.gamepads.entry(event.gamepad).or_default();
+            }
+            GamepadEventKind::Disconnected => {
+                let Some(pad) = self.gamepads.remove(&event.gamepad) else { return };
+                for button in pad.buttons {
+                    self.release(Binding::Button(button));
+                }
+                self.update_axis_actions();
+            }
+            GamepadEventKind::ButtonPressed(button) => {
+                self.gamepads.entry(event.gamepad).or_default().buttons.insert(button);
+                self.buttons_pressed_once.insert(button);
+                self.press(Binding::Button(button));
+            }
+            GamepadEventKind::ButtonReleased(button) => {
+                self.gamepads.entry(event.gamepad).or_default().buttons.remove(&button);
+                self.release(Binding::Button(button));
+            }
+            GamepadEventKind::AxisChanged(axis, value) => {
+                self.gamepads.entry(event.gamepad).or_default().axes.insert(axis, value);
+                self.update_axis_actions();
+            }
+        }
+    }
+
+    fn press(&mut self, binding: Binding) {
+        if let Some(&action) = self.keymap.get(&binding) {
+            self.pressed_actions.insert(action);
+            self.pressed_actions_once.insert(action);
+        }
+    }
+
+    fn release(&mut self, binding: Binding) {
+        if let Some(&action) = self.keymap.get(&binding) {
+            self.release_action(action);
+        }
+    }
+
+    /// Release `action` unless another key or button bound to it, on any
+    /// gamepad, is still held.
+    fn release_action(&mut self, action: A) {
+        let bound_held = self.keymap.iter().any(|(&binding, &a)| {
+            a == action && !matches!(binding, Binding::Axis(..)) && self.binding_value(binding) > 0.0
+        });
+        let chord_held = self.chords.iter().any(|(&(key, _), &a)| a == action && self.key_held(key));
+        if !bound_held && !chord_held && self.pressed_actions.remove(&action) {
+            self.released_actions_once.insert(action);
+        }
+    }
+
+    /// Hold the actions whose axis bindings are past the threshold.
+    fn update_axis_actions(&mut self) {
+        let held: HashSet<A> = self
+            .keymap
+            .iter()
+            .filter(|&(&binding, _)| {
+                matches!(binding, Binding::Axis(..)) && self.binding_value(binding) >= AXIS_PRESS_THRESHOLD
+            })
+            .map(|(_, &action)| action)
+            .collect();
+        self.pressed_actions_once.extend(held.difference(&self.axis_actions));
+        self.released_actions_once.extend(self.axis_actions.difference(&held));
+        self.axis_actions = held;
+    }
+
+    /// 1 for a held key or button, how far an axis binding is deflected in
+    /// its direction.
+    fn binding_value(&self, binding: Binding) -> f32 {
+        let held = match binding {
+            Binding::Key(key) => self.key_held(key),
+            Binding::Mouse(button) => self.mouse_held(button),
+            Binding::Wheel(_) => false,
+            Binding::Button(button) => self.gamepad_button_held(button),
+            Binding::Axis(axis, AxisDirection::Positive) => return self.gamepad_axis(axis).max(0.0),
+            Binding::Axis(axis, AxisDirection::Negative) => return (-self.gamepad_axis(axis)).max(0.0),
+        };
+        if held { 1.0 } else { 0.0 }
+    }
+
+    fn axis_input_value(&self, input: AxisInput) -> f32 {
+        match input {
+            AxisInput::Composite { negative, positive } => {
+                self.binding_value(positive) - self.binding_value(negative)
+            }
+            AxisInput::Gamepad(axis) => self.gamepad_axis(axis),
+            AxisInput::MouseX => self.mouse_delta.0 as f32,
+            AxisInput::MouseY => self.mouse_delta.1 as f32,
+            AxisInput::ScrollX => self.scroll_delta.0 as f32,
+            AxisInput::ScrollY => self.scroll_delta.1 as f32,
+        }
+    }
+
+    /// Whether `event` starts new input: a key or mouse button press, mouse
+    /// motion or scrolling. Releases are not, so keys never get stuck while
+    /// unfocused.
+    pub fn is_press<T>(event: &Event<T>) -> bool {
+        match event {
+            Event::WindowEvent { event: WindowEvent::KeyboardInput { event, .. }, .. } => {
+                event.state == ElementState::Pressed
+            }
+            Event::WindowEvent { event: WindowEvent::MouseInput { state, .. }, .. } => {
+                *state == ElementState::Pressed
+            }
+            Event::DeviceEvent { event: DeviceEvent::MouseMotion { .. }, .. } => true,
+            Event::WindowEvent { event: WindowEvent::MouseWheel { .. }, .. } => true,
+            _ => false,
+        }
+    }
+
     pub fn key_held(&self, key: KeyCode) -> bool {
         self.pressed_keys.contains(&key)
     }
     pub fn key_pressed(&self, key: KeyCode) -> bool {
         self.pressed_once.contains(&key)
     }
+    pub fn key_released(&self, key: KeyCode) -> bool {
+        self.released_once.contains(&key)
+    }
+
+    pub fn mouse_held(&self, button: MouseButton) -> bool {
+        self.pressed_mouse.contains(&button)
+    }
+    pub fn mouse_pressed(&self, button: MouseButton) -> bool {
+        self.mouse_pressed_once.contains(&button)
+    }
+    pub fn mouse_released(&self, button: MouseButton) -> bool {
+        self.mouse_released_once.contains(&button)
+    }
+
+    pub fn modifiers(&self) -> ModifiersState {
+        self.modifiers
+    }
 
     pub fn action_held(&self, action: A) -> bool {
-        self.pressed_actions.contains(&action)
+        self.pressed_actions.contains(&action) || self.axis_actions.contains(&action)
     }
     pub fn action_pressed(&self, action: A) -> bool {
         self.pressed_actions_once.contains(&action)
     }
+    pub fn action_released(&self, action: A) -> bool {
+        self.released_actions_once.contains(&action)
+    }
+
+    /// Value of a 1D analog action, the sum of its bindings.
+    pub fn axis(&self, action: A) -> f32 {
+        self.axis_2d(action).x
+    }
+
+    /// Value of a 2D analog action, the sum of its bindings.
+    pub fn axis_2d(&self, action: A) -> Vec2 {
+        let Some(bindings) = self.analog.get(&action) else {
+            return Vec2::ZERO;
+        };
+        bindings
+            .iter()
+            .map(|binding| {
+                let x = self.axis_input_value(binding.x);
+                let y = binding.y.map_or(0.0, |y| self.axis_input_value(y));
+                binding.process(Vec2::new(x, y))
+            })
+            .sum()
+    }
+
+    /// How strongly the action is held, from 0 to 1: 1 for keys and
+    /// buttons, the deflection for axis bindings.
+    pub fn action_value(&self, action: A) -> f32 {
+        let digital = if self.pressed_actions.contains(&action) { 1.0 } else { 0.0 };
+        self.keymap
+            .iter()
+            .filter(|&(_, &a)| a == action)
+            .map(|(&binding, _)| self.binding_value(binding))
+            .fold(digital, f32::max)
+    }
+
+    pub fn gamepad_button_held(&self, button: GamepadButton) -> bool {
+        self.gamepads.values().any(|pad| pad.buttons.contains(&button))
+    }
+    pub fn gamepad_button_pressed(&self, button: GamepadButton) -> bool {
+        self.buttons_pressed_once.contains(&button)
+    }
+
+    /// Value of `axis` after dead zone and response curve, from whichever
+    /// connected gamepad deflects it most.
+    pub fn gamepad_axis(&self, axis: GamepadAxis) -> f32 {
+        self.gamepads
+            .values()
+            .map(|pad| pad.value(axis, &self.gamepad_settings))
+            .fold(0.0, |best, v| if v.abs() > best.abs() { v } else { best })
+    }
+
+    pub fn gamepad_settings(&self) -> &GamepadSettings {
+        &self.gamepad_settings
+    }
+    pub fn gamepad_settings_mut(&mut self) -> &mut GamepadSettings {
+        &mut self.gamepad_settings
+    }
 
     pub fn mouse_delta(&self) -> (f64, f64) {
         self.mouse_delta
     }
 
+    /// Scrolled this frame in lines, away from the user positive on y.
+    pub fn scroll_delta(&self) -> (f64, f64) {
+        self.scroll_delta
+    }
+
     pub fn end_frame(&mut self) {
         self.mouse_delta = (0.0, 0.0);
+        self.scroll_delta = (0.0, 0.0);
         self.pressed_once.clear();
+        self.released_once.clear();
+        self.mouse_pressed_once.clear();
+        self.mouse_released_once.clear();
         self.pressed_actions_once.clear();
+        self.released_actions_once.clear();
+        self.buttons_pressed_once.clear();
     }
 } 
\ No newline at end of file
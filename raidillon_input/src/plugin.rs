@@ -0,0 +1,77 @@
+use std::any::type_name;
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+use hecs::World;
+use raidillon_ecs::{App, EventReader, Events, Plugin, Resources, Stage};
+
+use crate::{CursorGrab, GamepadEvent, GamepadEventKind, Input, InputFocus, WinitEvent, GAMEPAD_SYSTEM};
+
+/// Registers [`Input<A>`] and feeds it from the raw [`WinitEvent`] channel
+/// published by the window runner and from [`GamepadEvent`]s. Presses are
+/// dropped while the game view lacks [`InputFocus`], unless the plugin was
+/// created with [`global`](Self::global).
+pub struct InputPlugin<A> {
+    global:  bool,
+    _marker: PhantomData<fn() -> A>,
+}
+
+impl<A> Default for InputPlugin<A> {
+    fn default() -> Self {
+        Self { global: false, _marker: PhantomData }
+    }
+}
+
+impl<A> InputPlugin<A> {
+    /// Input that is received regardless of [`InputFocus`], e.g. editor
+    /// shortcuts.
+    pub fn global() -> Self {
+        Self { global: true, _marker: PhantomData }
+    }
+}
+
+impl<A: Copy + Eq + Hash + 'static> Plugin for InputPlugin<A> {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Input<A>>()
+            .init_resource::<CursorGrab>()
+            .init_resource::<InputFocus>()
+            .add_event::<WinitEvent>()
+            .add_event::<GamepadEvent>();
+        app.schedule
+            .add_system(Stage::PreUpdate, type_name::<Input<A>>(), input_system::<A>(self.global))
+            .after(GAMEPAD_SYSTEM);
+    }
+}
+
+/// Start a new input frame and apply every window/device and gamepad event
+/// received since the previous one.
+fn input_system<A: Copy + Eq + Hash + 'static>(global: bool) -> impl FnMut(&mut World, &mut Resources) {
+    let mut reader = EventReader::<WinitEvent>::default();
+    let mut gamepad_reader = EventReader::<GamepadEvent>::default();
+    move |_world, res| {
+        let focused = global || res.fetch::<InputFocus>().focused || res.fetch::<CursorGrab>().grabbed;
+        let events = res.fetch::<Events<WinitEvent>>();
+        let gamepad_events = res.fetch::<Events<GamepadEvent>>();
+        let mut input = res.fetch_mut::<Input<A>>();
+        input.end_frame();
+        for event in reader.read(&events) {
+            if focused || !Input::<A>::is_press(event) {
+                input.handle_event(event);
+            }
+        }
+        for event in gamepad_reader.read(&gamepad_events) {
+            match event.kind {
+                // Unfocused sticks read as centered rather than keeping
+                // their last deflection.
+                GamepadEventKind::AxisChanged(axis, _) if !focused => {
+                    input.handle_gamepad_event(&GamepadEvent {
+                        kind: GamepadEventKind::AxisChanged(axis, 0.0),
+                        ..*event
+                    });
+                }
+                _ if focused || !event.is_press() => input.handle_gamepad_event(event),
+                _ => {}
+            }
+        }
+    }
+}
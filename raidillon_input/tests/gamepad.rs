@@ -0,0 +1,48 @@
+use raidillon_input::{GamepadButton, GamepadEvent, GamepadEventKind, Input};
+use winit::event::ElementState;
+use winit::keyboard::KeyCode;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+enum Action {
+    Jump,
+    Crouch,
+}
+
+fn event(gamepad: usize, kind: GamepadEventKind) -> GamepadEvent {
+    GamepadEvent { gamepad, kind }
+}
+
+#[test]
+fn action_stays_held_while_another_pad_holds_the_button() {
+    let mut input = Input::new();
+    input.map_button(GamepadButton::South, Action::Jump);
+    input.handle_gamepad_event(&event(0, GamepadEventKind::ButtonPressed(GamepadButton::South)));
+    input.handle_gamepad_event(&event(1, GamepadEventKind::ButtonPressed(GamepadButton::South)));
+
+    input.handle_gamepad_event(&event(0, GamepadEventKind::ButtonReleased(GamepadButton::South)));
+    assert!(input.action_held(Action::Jump));
+
+    input.handle_gamepad_event(&event(1, GamepadEventKind::Disconnected));
+    assert!(!input.action_held(Action::Jump));
+}
+
+#[test]
+fn action_stays_held_while_another_binding_holds_it() {
+    let mut input = Input::new();
+    input.map_key(KeyCode::KeyC, Action::Crouch);
+    input.map_button(GamepadButton::East, Action::Crouch);
+
+    input.handle_key(KeyCode::KeyC, ElementState::Pressed);
+    input.handle_gamepad_event(&event(0, GamepadEventKind::ButtonPressed(GamepadButton::East)));
+    input.handle_key(KeyCode::KeyC, ElementState::Released);
+    assert!(input.action_held(Action::Crouch));
+    assert!(!input.action_released(Action::Crouch));
+
+    input.handle_key(KeyCode::KeyC, ElementState::Pressed);
+    input.handle_gamepad_event(&event(0, GamepadEventKind::ButtonReleased(GamepadButton::East)));
+    assert!(input.action_held(Action::Crouch));
+
+    input.handle_key(KeyCode::KeyC, ElementState::Released);
+    assert!(!input.action_held(Action::Crouch));
+    assert!(input.action_released(Action::Crouch));
+}
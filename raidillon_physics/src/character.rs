@@ -0,0 +1,185 @@
+//! Kinematic character movement.
+//!
+//! A [`CharacterController`] moves its entity as an upright capsule that
+//! collides with and slides along everything with a collider, climbs steps
+//! and gentle slopes, falls under gravity and can jump and crouch. Game code
+//! fills in the wanted movement every frame; the physics step does the rest.
+//! Characters pass through colliders their
+//! [`CollisionLayers`](crate::CollisionLayers) do not interact with.
+
+use glam::{Quat, Vec3};
+use hecs::World;
+use raidillon_ecs::Transform;
+use rapier3d::control::{CharacterAutostep, CharacterCollision, CharacterLength, KinematicCharacterController};
+use rapier3d::math::Pose;
+use rapier3d::parry::shape::Capsule;
+use rapier3d::pipeline::QueryFilter;
+use serde::{Deserialize, Serialize};
+
+use crate::components::CollisionLayers;
+use crate::world::{from_vector, to_pose, to_vector, PhysicsWorld};
+
+/// Moves the entity as a capsule standing on its `Transform` translation,
+/// which is the point between the character's feet.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CharacterController {
+    pub radius:         f32,
+    /// Height of the capsule standing up.
+    pub height:         f32,
+    /// Height of the capsule while crouching.
+    pub crouch_height:  f32,
+    /// Distance from the top of the capsule down to the eyes.
+    pub eye_offset:     f32,
+    /// Tallest ledge walked onto without jumping.
+    pub step_height:    f32,
+    /// Steepest climbable slope, in degrees.
+    pub max_slope:      f32,
+    pub jump_speed:     f32,
+    /// Mass used to push dynamic bodies around.
+    pub mass:           f32,
+    /// When false the character neither moves nor collides and its
+    /// `Transform` is left alone, e.g. for noclip.
+    pub enabled:        bool,
+
+    /// Wanted horizontal velocity, set by game code every frame.
+    #[serde(skip)]
+    pub movement:       Vec3,
+    /// Jump on the next step if standing on the ground.
+    #[serde(skip)]
+    pub jump:           bool,
+    /// Crouch while set; standing up waits until there is room.
+    #[serde(skip)]
+    pub crouch:         bool,
+    #[serde(skip)]
+    pub vertical_speed: f32,
+    #[serde(skip)]
+    pub grounded:       bool,
+    #[serde(skip)]
+    pub crouching:      bool,
+}
+
+impl Default for CharacterController {
+    fn default() -> Self {
+        Self {
+            radius:         0.3,
+            height:         1.8,
+            crouch_height:  1.0,
+            eye_offset:     0.15,
+            step_height:    0.3,
+            max_slope:      45.0,
+            jump_speed:     5.0,
+            mass:           80.0,
+            enabled:        true,
+            movement:       Vec3::ZERO,
+            jump:           false,
+            crouch:         false,
+            vertical_speed: 0.0,
+            grounded:       false,
+            crouching:      false,
+        }
+    }
+}
+
+impl CharacterController {
+    /// Current capsule height, lower while crouching.
+    pub fn current_height(&self) -> f32 {
+        if self.crouching { self.crouch_height } else { self.height }
+    }
+
+    /// Height of the eyes above the feet.
+    pub fn eye_height(&self) -> f32 {
+        self.current_height() - self.eye_offset
+    }
+
+    /// Current capsule with its feet at `feet`.
+    pub(crate) fn capsule_at(&self, feet: Vec3) -> (Capsule, Pose) {
+        let (capsule, center) = self.capsule(self.current_height());
+        (capsule, to_pose(feet + center, Quat::IDENTITY))
+    }
+
+    /// Capsule of the given total height and its center above the feet.
+    fn capsule(&self, height: f32) -> (Capsule, Vec3) {
+        let half_height = (height * 0.5 - self.radius).max(0.0);
+        (Capsule::new_y(half_height, self.radius), Vec3::Y * height * 0.5)
+    }
+}
+
+impl PhysicsWorld {
+    /// Move every enabled character by one timestep against the colliders
+    /// as of the last step.
+    pub(crate) fn move_characters(&mut self, world: &mut World) {
+        let dt = self.timestep;
+        let query = world.query_mut::<(&mut Transform, &mut CharacterController, Option<&CollisionLayers>)>();
+        for (entity, (transform, character, layers)) in query.into_iter() {
+            if !character.enabled {
+                character.vertical_speed = 0.0;
+                character.grounded = false;
+                continue;
+            }
+
+            let mut filter = QueryFilter::default()
+                .exclude_sensors()
+                .groups(self.groups(layers));
+            if let Some(body) = self.body_handle(entity) {
+                filter = filter.exclude_rigid_body(body);
+            }
+            let queries = self.rapier.query_pipeline_with_filter(filter);
+            let feet = transform.translation;
+
+            if character.crouch {
+                character.crouching = true;
+            } else if character.crouching {
+                // Only stand up if the standing capsule fits, lifted off the
+                // floor so that touching it does not count.
+                let (shape, center) = character.capsule(character.height);
+                let pose = to_pose(feet + center + Vec3::Y * 0.02, Quat::IDENTITY);
+                character.crouching = queries.intersect_shape(pose, &shape).next().is_some();
+            }
+
+            if character.jump && character.grounded {
+                character.vertical_speed = character.jump_speed;
+            }
+            character.jump = false;
+            character.vertical_speed += self.gravity.y * dt;
+
+            let kcc = KinematicCharacterController {
+                offset: CharacterLength::Absolute(0.01),
+                autostep: Some(CharacterAutostep {
+                    max_height:             CharacterLength::Absolute(character.step_height),
+                    min_width:              CharacterLength::Absolute(character.radius * 0.5),
+                    include_dynamic_bodies: false,
+                }),
+                max_slope_climb_angle: character.max_slope.to_radians(),
+                min_slope_slide_angle: character.max_slope.to_radians(),
+                snap_to_ground: (character.vertical_speed <= 0.0)
+                    .then_some(CharacterLength::Absolute(character.step_height)),
+                ..Default::default()
+            };
+
+            let (shape, pose) = character.capsule_at(feet);
+            let desired = character.movement * dt + Vec3::Y * character.vertical_speed * dt;
+            let mut collisions: Vec<CharacterCollision> = Vec::new();
+            let moved = kcc.move_shape(dt, &queries, &shape, &pose, to_vector(desired), |c| collisions.push(c));
+            let moved_by = from_vector(moved.translation);
+
+            transform.translation = feet + moved_by;
+            character.grounded = moved.grounded;
+            // Landed, or bumped the head.
+            if (moved.grounded && character.vertical_speed < 0.0)
+                || (character.vertical_speed > 0.0 && moved_by.y < desired.y * 0.5)
+            {
+                character.vertical_speed = 0.0;
+            }
+
+            let rapier = &mut self.rapier;
+            let mut queries = rapier.broad_phase.as_query_pipeline_mut(
+                rapier.narrow_phase.query_dispatcher(),
+                &mut rapier.bodies,
+                &mut rapier.colliders,
+                filter,
+            );
+            kcc.solve_character_collision_impulses(dt, &mut queries, &shape, character.mass, &collisions);
+        }
+    }
+}
@@ -0,0 +1,161 @@
+use glam::Vec3;
+use raidillon_core::MeshGeometry;
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum BodyKind {
+    /// Moved by forces and collisions.
+    #[default]
+    Dynamic,
+    /// Never moves on its own.
+    Static,
+    /// Follows its `Transform`, pushing dynamic bodies out of the way.
+    Kinematic,
+}
+
+impl BodyKind {
+    pub const ALL: [BodyKind; 3] = [BodyKind::Dynamic, BodyKind::Static, BodyKind::Kinematic];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            BodyKind::Dynamic   => "Dynamic",
+            BodyKind::Static    => "Static",
+            BodyKind::Kinematic => "Kinematic",
+        }
+    }
+}
+
+// Scenes keep components as `ron::Value`, which drops enum variant names,
+// so the kind is stored as a plain string.
+impl Serialize for BodyKind {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
+impl<'de> Deserialize<'de> for BodyKind {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        BodyKind::ALL
+            .into_iter()
+            .find(|k| k.name() == name)
+            .ok_or_else(|| D::Error::unknown_variant(&name, &["Dynamic", "Static", "Kinematic"]))
+    }
+}
+
+/// A simulated body. The simulation writes the `Transform` of dynamic
+/// bodies; static and kinematic ones follow their `Transform`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RigidBody {
+    pub kind:            BodyKind,
+    pub gravity_scale:   f32,
+    pub linear_damping:  f32,
+    pub angular_damping: f32,
+    /// Keep the body from rotating, e.g. for characters.
+    pub lock_rotation:   bool,
+}
+
+impl Default for RigidBody {
+    fn default() -> Self {
+        Self {
+            kind:            BodyKind::Dynamic,
+            gravity_scale:   1.0,
+            linear_damping:  0.0,
+            angular_damping: 0.05,
+            lock_rotation:   false,
+        }
+    }
+}
+
+impl RigidBody {
+    pub fn new(kind: BodyKind) -> Self {
+        Self { kind, ..Default::default() }
+    }
+}
+
+/// Velocity of a body in world space, angular in radians per second. Read
+/// back after every step; writing it changes the body's velocity.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Velocity {
+    pub linear:  Vec3,
+    pub angular: Vec3,
+}
+
+/// Collision shape in the entity's local space. The `Transform` scale is
+/// applied on top. Tagged with a `type` field so it survives scene values.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum ColliderShape {
+    Box { half_extents: Vec3 },
+    Sphere { radius: f32 },
+    /// Along the local Y axis, `half_height` not counting the caps.
+    Capsule { half_height: f32, radius: f32 },
+    ConvexHull { points: Vec<Vec3> },
+    TriMesh { vertices: Vec<Vec3>, indices: Vec<[u32; 3]> },
+    /// Triangles of the entity's `ModelPath`, loaded when the collider is
+    /// created. Like any triangle mesh, best kept on static bodies.
+    Model,
+}
+
+impl ColliderShape {
+    /// Triangle mesh from a mesh's geometry.
+    pub fn trimesh(mesh: &MeshGeometry) -> Self {
+        Self::TriMesh { vertices: mesh.positions.clone(), indices: triangles(&mesh.indices) }
+    }
+
+    /// Convex hull around the vertices of a mesh.
+    pub fn convex_hull(mesh: &MeshGeometry) -> Self {
+        Self::ConvexHull { points: mesh.positions.clone() }
+    }
+}
+
+/// Group a triangle list into triangles.
+pub(crate) fn triangles(indices: &[u32]) -> Vec<[u32; 3]> {
+    indices.chunks_exact(3).map(|t| [t[0], t[1], t[2]]).collect()
+}
+
+/// Collision shape of an entity. Without a [`RigidBody`] the entity is
+/// treated as static.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Collider {
+    pub shape:    ColliderShape,
+    /// Mass per unit volume, for dynamic bodies.
+    pub density:  f32,
+    /// Physics material by name. When empty, model colliders pick one per
+    /// glTF material, see [`material`](crate::material).
+    pub material: String,
+}
+
+impl Default for Collider {
+    fn default() -> Self {
+        Self::new(ColliderShape::Box { half_extents: Vec3::splat(0.5) })
+    }
+}
+
+impl Collider {
+    pub fn new(shape: ColliderShape) -> Self {
+        Self { shape, density: 1.0, material: String::new() }
+    }
+}
+
+/// Collision layers an entity is on, by name, see
+/// [`LayerMatrix`](crate::LayerMatrix). Scene queries only see entities on a
+/// layer in their mask; without this component an entity is on `default`.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct CollisionLayers(pub Vec<String>);
+
+impl Default for CollisionLayers {
+    fn default() -> Self {
+        Self::new(&["default"])
+    }
+}
+
+impl CollisionLayers {
+    pub fn new(names: &[&str]) -> Self {
+        Self(names.iter().map(|&name| name.to_owned()).collect())
+    }
+}
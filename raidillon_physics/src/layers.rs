@@ -0,0 +1,85 @@
+//! Named collision layers and which of them collide.
+
+use anyhow::{bail, Result};
+
+use crate::components::CollisionLayers;
+
+/// Layers are bits of a `u32`.
+pub const MAX_LAYERS: usize = 32;
+
+/// Names of the collision layers and a symmetric matrix of which layers
+/// interact. Layer 0 is `default`; every pair interacts until told
+/// otherwise. Non-interacting colliders pass through each other, and
+/// characters pass through colliders their layers do not interact with.
+#[derive(Clone, Debug)]
+pub struct LayerMatrix {
+    names: Vec<String>,
+    /// Bit `j` of row `i` is set when layers `i` and `j` interact.
+    rows:  [u32; MAX_LAYERS],
+}
+
+impl Default for LayerMatrix {
+    fn default() -> Self {
+        Self { names: vec!["default".to_owned()], rows: [u32::MAX; MAX_LAYERS] }
+    }
+}
+
+impl LayerMatrix {
+    /// Index of the layer called `name`, adding it if needed.
+    pub fn add(&mut self, name: &str) -> Result<u32> {
+        if let Some(index) = self.index(name) {
+            return Ok(index);
+        }
+        if self.names.len() == MAX_LAYERS {
+            bail!("no room for collision layer {name:?}, all {MAX_LAYERS} are named");
+        }
+        self.names.push(name.to_owned());
+        Ok(self.names.len() as u32 - 1)
+    }
+
+    pub fn index(&self, name: &str) -> Option<u32> {
+        self.names.iter().position(|n| n == name).map(|i| i as u32)
+    }
+
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// Bits of the layers with the given names, e.g. for a query mask.
+    pub fn mask(&self, names: &[&str]) -> Result<u32> {
+        names.iter().try_fold(0, |mask, name| match self.index(name) {
+            Some(index) => Ok(mask | 1 << index),
+            None => bail!("unknown collision layer {name:?}"),
+        })
+    }
+
+    /// Bits of an entity's layers, `default` without a [`CollisionLayers`]
+    /// component. Names that are not layers are left out.
+    pub fn bits(&self, layers: Option<&CollisionLayers>) -> u32 {
+        match layers {
+            Some(layers) => layers.0.iter().filter_map(|name| self.index(name)).fold(0, |bits, i| bits | 1 << i),
+            None => 1,
+        }
+    }
+
+    pub fn interacts(&self, a: u32, b: u32) -> bool {
+        self.rows[a as usize] & (1 << b) != 0
+    }
+
+    pub fn set_interacts(&mut self, a: u32, b: u32, interacts: bool) {
+        for (row, bit) in [(a, b), (b, a)] {
+            if interacts {
+                self.rows[row as usize] |= 1 << bit;
+            } else {
+                self.rows[row as usize] &= !(1 << bit);
+            }
+        }
+    }
+
+    /// Every layer that interacts with at least one of the layers in `bits`.
+    pub fn filter(&self, bits: u32) -> u32 {
+        (0..MAX_LAYERS)
+            .filter(|&i| bits & (1 << i) != 0)
+            .fold(0, |mask, i| mask | self.rows[i])
+    }
+}
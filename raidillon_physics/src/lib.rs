@@ -0,0 +1,17 @@
+pub mod character;
+pub mod components;
+pub mod layers;
+pub mod material;
+pub mod plugin;
+pub mod query;
+pub mod trigger;
+pub mod world;
+
+pub use character::CharacterController;
+pub use components::{BodyKind, Collider, ColliderShape, CollisionLayers, RigidBody, Velocity};
+pub use layers::LayerMatrix;
+pub use material::{PhysicsConfig, PhysicsMaterial};
+pub use plugin::PhysicsPlugin;
+pub use query::{overlap, raycast, sphere_cast, Hit, ALL_LAYERS};
+pub use trigger::{Trigger, TriggerEnter, TriggerExit};
+pub use world::PhysicsWorld;
@@ -0,0 +1,109 @@
+//! Physics materials and the physics config file.
+//!
+//! Materials are looked up by name. A collider uses its own
+//! [`material`](crate::Collider::material) when set; model colliders
+//! otherwise take, per glTF primitive, the material named by the glTF
+//! material's `extras` (`{"physics_material": "ice"}`), or the physics
+//! material with the same name as the glTF material.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use raidillon_core::GeometryPart;
+use serde::{Deserialize, Serialize};
+
+use crate::layers::LayerMatrix;
+use crate::world::PhysicsWorld;
+
+/// Surface response of a collider.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PhysicsMaterial {
+    pub friction:    f32,
+    /// Bounciness, 0 for none.
+    pub restitution: f32,
+    /// Layer to put colliders with this material on instead of the entity's
+    /// own layers, e.g. `foliage`.
+    pub layer:       Option<String>,
+}
+
+impl Default for PhysicsMaterial {
+    fn default() -> Self {
+        Self { friction: 0.5, restitution: 0.0, layer: None }
+    }
+}
+
+/// Layers, layer interactions and materials, usually from a RON file:
+///
+/// ```ron
+/// (
+///     layers: ["player", "foliage"],
+///     ignore: [("player", "foliage")],
+///     materials: {
+///         "ice": (friction: 0.02),
+///         "leaves": (layer: Some("foliage")),
+///     },
+/// )
+/// ```
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PhysicsConfig {
+    /// Layer names after `default`, in bit order.
+    pub layers:    Vec<String>,
+    /// Pairs of layers that pass through each other.
+    pub ignore:    Vec<(String, String)>,
+    pub materials: BTreeMap<String, PhysicsMaterial>,
+}
+
+impl PhysicsConfig {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path).with_context(|| format!("reading physics config {path:?}"))?;
+        ron::from_str(&text).with_context(|| format!("parsing physics config {path:?}"))
+    }
+}
+
+impl PhysicsWorld {
+    /// Replace the layers and materials. Every collider is rebuilt on the
+    /// next step to pick up the change.
+    pub fn apply_config(&mut self, config: &PhysicsConfig) -> Result<()> {
+        let mut layers = LayerMatrix::default();
+        for name in &config.layers {
+            layers.add(name)?;
+        }
+        for (a, b) in &config.ignore {
+            let index = |name: &str| layers.index(name).with_context(|| format!("unknown collision layer {name:?}"));
+            let (a, b) = (index(a)?, index(b)?);
+            layers.set_interacts(a, b, false);
+        }
+        self.layers = layers;
+        self.materials = config.materials.clone();
+        self.rebuild_colliders();
+        Ok(())
+    }
+}
+
+/// Name of the material for a collider part, see the module docs.
+pub(crate) fn material_name<'a>(
+    materials: &BTreeMap<String, PhysicsMaterial>,
+    own: &'a str,
+    part: Option<&'a GeometryPart>,
+) -> Option<&'a str> {
+    if !own.is_empty() {
+        return Some(own);
+    }
+    let part = part?;
+    let from_extras = part.extras.as_deref().and_then(extras_material);
+    from_extras.or_else(|| part.material.as_deref().filter(|name| materials.contains_key(*name)))
+}
+
+/// `physics_material` entry of glTF material extras.
+fn extras_material(extras: &str) -> Option<&str> {
+    #[derive(Deserialize)]
+    struct Extras<'a> {
+        #[serde(borrow)]
+        physics_material: Option<&'a str>,
+    }
+    serde_json::from_str::<Extras>(extras).ok()?.physics_material
+}
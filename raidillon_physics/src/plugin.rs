@@ -0,0 +1,90 @@
+use std::path::PathBuf;
+
+use hecs::World;
+use raidillon_ecs::{App, Events, Plugin, Resources, Stage};
+
+use crate::character::CharacterController;
+use crate::components::{Collider, CollisionLayers, RigidBody, Velocity};
+use crate::material::PhysicsConfig;
+use crate::trigger::{Trigger, TriggerEnter, TriggerExit};
+use crate::world::PhysicsWorld;
+
+/// Steps the [`PhysicsWorld`] every fixed update and sends its trigger
+/// events, and makes the physics components part of saved scenes and
+/// reflection.
+#[derive(Default)]
+pub struct PhysicsPlugin {
+    /// [`PhysicsConfig`] file with the collision layers and materials.
+    pub config: Option<PathBuf>,
+}
+
+impl PhysicsPlugin {
+    pub fn with_config(path: impl Into<PathBuf>) -> Self {
+        Self { config: Some(path.into()) }
+    }
+}
+
+impl Plugin for PhysicsPlugin {
+    fn build(&self, app: &mut App) {
+        let mut physics = PhysicsWorld::new(app.schedule.fixed_timestep());
+        if let Some(path) = &self.config {
+            if let Err(err) = PhysicsConfig::load(path).and_then(|config| physics.apply_config(&config)) {
+                eprintln!("[physics] {err:#}");
+            }
+        }
+
+        app.insert_resource(physics)
+            .add_event::<TriggerEnter>()
+            .add_event::<TriggerExit>()
+            .register_scene_component::<RigidBody>("RigidBody")
+            .register_scene_component::<Collider>("Collider")
+            .register_scene_component::<Velocity>("Velocity")
+            .register_scene_component::<CharacterController>("CharacterController")
+            .register_scene_component::<CollisionLayers>("CollisionLayers")
+            .register_scene_component::<Trigger>("Trigger")
+            .reflect::<RigidBody>("RigidBody", |b| {
+                b.insertable()
+                    .field_range("gravity_scale", -10.0..=10.0, |b| &mut b.gravity_scale)
+                    .field_range("linear_damping", 0.0..=100.0, |b| &mut b.linear_damping)
+                    .field_range("angular_damping", 0.0..=100.0, |b| &mut b.angular_damping)
+                    .field("lock_rotation", |b| &mut b.lock_rotation);
+            })
+            .reflect::<Collider>("Collider", |c| {
+                c.insertable()
+                    .field_range("density", 0.0..=100_000.0, |c| &mut c.density)
+                    .field("material", |c| &mut c.material);
+            })
+            .reflect::<Velocity>("Velocity", |v| {
+                v.insertable()
+                    .field("linear", |v| &mut v.linear)
+                    .field("angular", |v| &mut v.angular);
+            })
+            .reflect::<Trigger>("Trigger", |t| {
+                t.insertable().field("tag", |t| &mut t.tag);
+            })
+            .reflect::<CharacterController>("CharacterController", |c| {
+                c.insertable()
+                    .field_range("radius", 0.05..=2.0, |c| &mut c.radius)
+                    .field_range("height", 0.1..=5.0, |c| &mut c.height)
+                    .field_range("crouch_height", 0.1..=5.0, |c| &mut c.crouch_height)
+                    .field_range("eye_offset", 0.0..=1.0, |c| &mut c.eye_offset)
+                    .field_range("step_height", 0.0..=1.0, |c| &mut c.step_height)
+                    .field_range("max_slope", 0.0..=89.0, |c| &mut c.max_slope)
+                    .field_range("jump_speed", 0.0..=20.0, |c| &mut c.jump_speed)
+                    .field_range("mass", 0.0..=1000.0, |c| &mut c.mass)
+                    .field("enabled", |c| &mut c.enabled);
+            })
+            .add_system(Stage::FixedUpdate, "physics_step", physics_step);
+    }
+}
+
+fn physics_step(world: &mut World, res: &mut Resources) {
+    let mut physics = res.fetch_mut::<PhysicsWorld>();
+    physics.step(world);
+
+    let (enters, exits) = physics.drain_trigger_events();
+    let mut enter_events = res.fetch_mut::<Events<TriggerEnter>>();
+    enters.into_iter().for_each(|e| enter_events.send(e));
+    let mut exit_events = res.fetch_mut::<Events<TriggerExit>>();
+    exits.into_iter().for_each(|e| exit_events.send(e));
+}
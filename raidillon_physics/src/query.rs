@@ -0,0 +1,165 @@
+//! Scene queries for gameplay: rays, swept spheres and overlaps.
+//!
+//! The free functions use the [`PhysicsWorld`] resource when there is one,
+//! and otherwise test against the [`MeshBounds`] of rendered models so tools
+//! without physics can still query the scene. Only entities on a layer in
+//! `mask` are considered, see [`CollisionLayers`]; without physics only the
+//! `default` layer is known.
+
+use glam::{Quat, Vec3};
+use hecs::{Entity, World};
+use raidillon_core::{Aabb, Ray};
+use raidillon_ecs::{Resources, Transform};
+use raidillon_ecs::MeshBounds;
+use rapier3d::parry::query::{Ray as RapierRay, ShapeCastOptions};
+use rapier3d::parry::shape::Ball;
+use rapier3d::pipeline::QueryFilter;
+
+use crate::components::CollisionLayers;
+use crate::layers::LayerMatrix;
+use crate::world::{from_vector, mask_groups, to_pose, to_vector, PhysicsWorld};
+
+/// Mask that matches every layer.
+pub const ALL_LAYERS: u32 = u32::MAX;
+
+/// Closest thing a ray or swept sphere ran into.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Hit {
+    pub entity:   Entity,
+    /// Point on the surface that was hit, in world space.
+    pub point:    Vec3,
+    pub normal:   Vec3,
+    /// Distance travelled along the ray before the hit.
+    pub distance: f32,
+}
+
+/// First entity hit by `ray` within `max_distance`.
+pub fn raycast(world: &World, res: &Resources, ray: &Ray, max_distance: f32, mask: u32) -> Option<Hit> {
+    match res.get::<PhysicsWorld>() {
+        Some(physics) => physics.raycast(ray, max_distance, mask),
+        None => bounds_cast(world, ray, 0.0, max_distance, mask),
+    }
+}
+
+/// First entity hit by a sphere of `radius` swept along `ray`.
+pub fn sphere_cast(world: &World, res: &Resources, ray: &Ray, radius: f32, max_distance: f32, mask: u32) -> Option<Hit> {
+    match res.get::<PhysicsWorld>() {
+        Some(physics) => physics.sphere_cast(ray, radius, max_distance, mask),
+        None => bounds_cast(world, ray, radius, max_distance, mask),
+    }
+}
+
+/// Entities touching the sphere at `center`.
+pub fn overlap(world: &World, res: &Resources, center: Vec3, radius: f32, mask: u32) -> Vec<Entity> {
+    match res.get::<PhysicsWorld>() {
+        Some(physics) => physics.overlap(center, radius, mask),
+        None => bounds_overlap(world, center, radius, mask),
+    }
+}
+
+impl PhysicsWorld {
+    /// First collider hit by `ray` within `max_distance`. Sensors are skipped.
+    pub fn raycast(&self, ray: &Ray, max_distance: f32, mask: u32) -> Option<Hit> {
+        let dir = ray.dir.try_normalize()?;
+        let cast = RapierRay::new(to_vector(ray.origin), to_vector(dir));
+        let (collider, hit) = self
+            .rapier
+            .query_pipeline_with_filter(query_filter(mask))
+            .cast_ray_and_get_normal(&cast, max_distance, true)?;
+        Some(Hit {
+            entity:   self.collider_entity(collider)?,
+            point:    ray.origin + dir * hit.time_of_impact,
+            normal:   from_vector(hit.normal),
+            distance: hit.time_of_impact,
+        })
+    }
+
+    /// First collider hit by a sphere of `radius` swept along `ray`.
+    pub fn sphere_cast(&self, ray: &Ray, radius: f32, max_distance: f32, mask: u32) -> Option<Hit> {
+        let dir = ray.dir.try_normalize()?;
+        let options = ShapeCastOptions {
+            max_time_of_impact: max_distance,
+            stop_at_penetration: true,
+            ..Default::default()
+        };
+        let (collider, hit) = self.rapier.query_pipeline_with_filter(query_filter(mask)).cast_shape(
+            &to_pose(ray.origin, Quat::IDENTITY),
+            to_vector(dir),
+            &Ball::new(radius),
+            options,
+        )?;
+        Some(Hit {
+            entity:   self.collider_entity(collider)?,
+            point:    from_vector(hit.witness1),
+            normal:   from_vector(hit.normal1),
+            distance: hit.time_of_impact,
+        })
+    }
+
+    /// Entities whose colliders touch the sphere at `center`.
+    pub fn overlap(&self, center: Vec3, radius: f32, mask: u32) -> Vec<Entity> {
+        let ball = Ball::new(radius);
+        let queries = self.rapier.query_pipeline_with_filter(query_filter(mask));
+        let mut entities: Vec<Entity> = queries
+            .intersect_shape(to_pose(center, Quat::IDENTITY), &ball)
+            .filter_map(|(collider, _)| self.collider_entity(collider))
+            .collect();
+        // Model colliders have one collider per part.
+        entities.sort_unstable();
+        entities.dedup();
+        entities
+    }
+}
+
+fn query_filter(mask: u32) -> QueryFilter<'static> {
+    QueryFilter::default().exclude_sensors().groups(mask_groups(mask))
+}
+
+/// World space model bounds of the entities on a layer in `mask`.
+fn model_bounds(world: &World, mask: u32) -> Vec<(Entity, Aabb)> {
+    let matrix = LayerMatrix::default();
+    world
+        .query::<(&Transform, &MeshBounds, Option<&CollisionLayers>)>()
+        .iter()
+        .filter(|(_, (_, _, layers))| matrix.bits(*layers) & mask != 0)
+        .map(|(entity, (transform, bounds, _))| (entity, bounds.0.transformed(&transform.matrix())))
+        .collect()
+}
+
+/// Ray or swept sphere against model bounds grown by `radius`.
+fn bounds_cast(world: &World, ray: &Ray, radius: f32, max_distance: f32, mask: u32) -> Option<Hit> {
+    let dir = ray.dir.try_normalize()?;
+    let ray = Ray::new(ray.origin, dir);
+    let (entity, aabb, distance) = model_bounds(world, mask)
+        .into_iter()
+        .filter_map(|(entity, aabb)| {
+            let grown = Aabb { min: aabb.min - Vec3::splat(radius), max: aabb.max + Vec3::splat(radius) };
+            let t = ray.intersect_aabb(&grown)?;
+            (t <= max_distance).then_some((entity, grown, t))
+        })
+        .min_by(|a, b| a.2.total_cmp(&b.2))?;
+
+    let normal = box_normal(&aabb, ray.at(distance));
+    Some(Hit { entity, point: ray.at(distance) - normal * radius, normal, distance })
+}
+
+fn bounds_overlap(world: &World, center: Vec3, radius: f32, mask: u32) -> Vec<Entity> {
+    model_bounds(world, mask)
+        .into_iter()
+        .filter(|(_, aabb)| center.clamp(aabb.min, aabb.max).distance_squared(center) <= radius * radius)
+        .map(|(entity, _)| entity)
+        .collect()
+}
+
+/// Outward normal of the box face closest to `point`.
+fn box_normal(aabb: &Aabb, point: Vec3) -> Vec3 {
+    let local = (point - aabb.center()) / aabb.half_extents().max(Vec3::splat(f32::EPSILON));
+    let abs = local.abs();
+    if abs.x >= abs.y && abs.x >= abs.z {
+        Vec3::X * local.x.signum()
+    } else if abs.y >= abs.z {
+        Vec3::Y * local.y.signum()
+    } else {
+        Vec3::Z * local.z.signum()
+    }
+}
@@ -0,0 +1,102 @@
+//! Trigger volumes.
+//!
+//! An entity with a [`Trigger`] and a [`Collider`](crate::Collider) becomes a
+//! sensor: nothing collides with it, and after every step it is tested
+//! against the other colliders and the characters. Entities with a matching
+//! [`Tag`] that start or stop touching it produce [`TriggerEnter`] and
+//! [`TriggerExit`] events. Only entities on layers that interact with the
+//! trigger's are noticed.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use hecs::{Entity, World};
+use raidillon_ecs::{Tag, Transform};
+use rapier3d::parry::query::intersection_test;
+use rapier3d::pipeline::QueryFilter;
+use serde::{Deserialize, Serialize};
+
+use crate::character::CharacterController;
+use crate::components::CollisionLayers;
+use crate::world::PhysicsWorld;
+
+/// Makes the entity's collider a trigger volume for entities tagged `tag`,
+/// or for every entity when `tag` is empty.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Trigger {
+    pub tag: String,
+}
+
+/// `entity` started touching `trigger`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TriggerEnter {
+    pub trigger: Entity,
+    pub entity:  Entity,
+}
+
+/// `entity` stopped touching `trigger`, or was despawned while inside.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TriggerExit {
+    pub trigger: Entity,
+    pub entity:  Entity,
+}
+
+/// Entities inside each trigger and the events not yet handed out.
+#[derive(Default)]
+pub(crate) struct TriggerState {
+    inside: BTreeMap<Entity, BTreeSet<Entity>>,
+    enters: Vec<TriggerEnter>,
+    exits:  Vec<TriggerExit>,
+}
+
+impl PhysicsWorld {
+    /// Events from the steps since the last call, in the order they happened.
+    pub fn drain_trigger_events(&mut self) -> (Vec<TriggerEnter>, Vec<TriggerExit>) {
+        let state = &mut self.triggers;
+        (std::mem::take(&mut state.enters), std::mem::take(&mut state.exits))
+    }
+
+    pub(crate) fn update_triggers(&mut self, world: &World) {
+        let mut inside = BTreeMap::new();
+        for (trigger, desc) in world.query::<&Trigger>().iter() {
+            let matches = |entity: Entity| {
+                desc.tag.is_empty() || world.get::<&Tag>(entity).is_ok_and(|tag| tag.0 == desc.tag)
+            };
+            let mut touching = BTreeSet::new();
+            for handle in self.collider_handles(trigger) {
+                let collider = &self.rapier.colliders[handle];
+                let (shape, pose, groups) = (collider.shape(), *collider.position(), collider.collision_groups());
+
+                let filter = QueryFilter::default().exclude_sensors().exclude_collider(handle).groups(groups);
+                let queries = self.rapier.query_pipeline_with_filter(filter);
+                touching.extend(
+                    queries
+                        .intersect_shape(pose, shape)
+                        .filter_map(|(collider, _)| self.collider_entity(collider))
+                        .filter(|&entity| entity != trigger && matches(entity)),
+                );
+
+                let mut characters = world.query::<(&Transform, &CharacterController, Option<&CollisionLayers>)>();
+                for (entity, (transform, character, layers)) in characters.iter() {
+                    if !character.enabled || entity == trigger || !matches(entity) || !groups.test(self.groups(layers)) {
+                        continue;
+                    }
+                    let (capsule, capsule_pose) = character.capsule_at(transform.translation);
+                    if intersection_test(&pose, shape, &capsule_pose, &capsule).is_ok_and(|i| i.intersecting) {
+                        touching.insert(entity);
+                    }
+                }
+            }
+            inside.insert(trigger, touching);
+        }
+
+        let state = &mut self.triggers;
+        let empty = BTreeSet::new();
+        for (&trigger, now) in &inside {
+            let before = state.inside.get(&trigger).unwrap_or(&empty);
+            state.exits.extend(before.difference(now).map(|&entity| TriggerExit { trigger, entity }));
+            state.enters.extend(now.difference(before).map(|&entity| TriggerEnter { trigger, entity }));
+        }
+        state.inside = inside;
+    }
+}
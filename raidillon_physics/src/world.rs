@@ -0,0 +1,376 @@
+//! The simulation behind the physics components.
+//!
+//! [`PhysicsWorld`] mirrors every entity that has a [`RigidBody`] or a
+//! [`Collider`] into a rapier world. Each [`step`](PhysicsWorld::step) first
+//! picks up new, changed and removed components and transforms moved from
+//! outside, e.g. by the editor, then advances the simulation by the fixed
+//! timestep, writes dynamic bodies back into their `Transform` and
+//! [`Velocity`], moves [characters](crate::CharacterController) and updates
+//! [triggers](crate::Trigger).
+//!
+//! Colliders interact according to the [`LayerMatrix`] and take friction and
+//! restitution from [materials](crate::material). A model collider becomes
+//! one rapier collider per glTF primitive, each with its own material.
+//!
+//! Bodies are created in query order and stepped on one thread, so the same
+//! world and inputs always give the same result.
+
+use std::collections::{BTreeMap, HashMap};
+use std::rc::Rc;
+
+use anyhow::{anyhow, Context, Result};
+use glam::{Quat, Vec3};
+use hecs::{Entity, Or, World};
+use raidillon_ecs::{ModelPath, Transform};
+use raidillon_core::{load_gltf_geometry, GeometryPart};
+use rapier3d::math::{Pose, Rotation, Vector};
+use rapier3d::pipeline::PhysicsWorld as RapierWorld;
+use rapier3d::prelude::{
+    ColliderBuilder, ColliderHandle, Group, InteractionGroups, InteractionTestMode, RigidBodyBuilder, RigidBodyHandle,
+    RigidBodyType,
+};
+
+use crate::components::{triangles, BodyKind, Collider, ColliderShape, CollisionLayers, RigidBody, Velocity};
+use crate::layers::LayerMatrix;
+use crate::material::{material_name, PhysicsMaterial};
+use crate::trigger::{Trigger, TriggerState};
+
+/// Rapier builds on a newer glam; convert through arrays.
+pub(crate) fn to_vector(v: Vec3) -> Vector {
+    Vector::from_array(v.to_array())
+}
+
+pub(crate) fn from_vector(v: Vector) -> Vec3 {
+    Vec3::from_array(v.to_array())
+}
+
+pub(crate) fn to_pose(translation: Vec3, rotation: Quat) -> Pose {
+    Pose::from_parts(to_vector(translation), Rotation::from_array(rotation.to_array()))
+}
+
+pub(crate) fn from_rotation(r: Rotation) -> Quat {
+    Quat::from_array(r.to_array())
+}
+
+/// Rapier handles of one entity and the component values they were built
+/// from, to notice changes.
+struct BodyEntry {
+    body:      RigidBodyHandle,
+    parts:     Vec<ColliderPart>,
+    desc:      RigidBody,
+    /// Collider, scale and model path the rapier colliders were built from.
+    shape:     Option<(Collider, Vec3, Option<String>)>,
+    sensor:    bool,
+    transform: Transform,
+    velocity:  Velocity,
+}
+
+/// One rapier collider of an entity.
+struct ColliderPart {
+    handle: ColliderHandle,
+    /// Layer bits set by the part's material, replacing the entity's.
+    layers: Option<u32>,
+}
+
+/// Primitives of a model, shared by all its colliders.
+type MeshData = Rc<Vec<GeometryPart>>;
+
+pub struct PhysicsWorld {
+    pub gravity:         Vec3,
+    /// Seconds per step, normally the schedule's fixed timestep.
+    pub timestep:        f32,
+    pub(crate) rapier:   RapierWorld,
+    pub layers:          LayerMatrix,
+    /// Materials by name, applied when colliders are built.
+    pub materials:       BTreeMap<String, PhysicsMaterial>,
+    /// Ordered so removals happen in the same order on every run.
+    bodies:              BTreeMap<Entity, BodyEntry>,
+    meshes:              HashMap<String, MeshData>,
+    pub(crate) triggers: TriggerState,
+}
+
+impl Default for PhysicsWorld {
+    fn default() -> Self {
+        Self::new(1.0 / 60.0)
+    }
+}
+
+impl PhysicsWorld {
+    pub fn new(timestep: f32) -> Self {
+        Self {
+            gravity: Vec3::new(0.0, -9.81, 0.0),
+            timestep,
+            rapier: RapierWorld::new(),
+            layers: LayerMatrix::default(),
+            materials: BTreeMap::new(),
+            bodies: BTreeMap::new(),
+            meshes: HashMap::new(),
+            triggers: TriggerState::default(),
+        }
+    }
+
+    /// The underlying rapier world, for anything not wrapped here.
+    pub fn rapier(&self) -> &RapierWorld {
+        &self.rapier
+    }
+
+    pub fn rapier_mut(&mut self) -> &mut RapierWorld {
+        &mut self.rapier
+    }
+
+    /// Entity a rapier collider belongs to.
+    pub fn collider_entity(&self, collider: ColliderHandle) -> Option<Entity> {
+        let bits = self.rapier.colliders.get(collider)?.user_data;
+        Entity::from_bits(bits as u64)
+    }
+
+    /// Rapier colliders of an entity, once it has been picked up by a step.
+    pub fn collider_handles(&self, entity: Entity) -> impl Iterator<Item = ColliderHandle> + '_ {
+        self.bodies.get(&entity).into_iter().flat_map(|e| e.parts.iter().map(|p| p.handle))
+    }
+
+    /// Rapier body of an entity, once it has been picked up by a step.
+    pub fn body_handle(&self, entity: Entity) -> Option<RigidBodyHandle> {
+        self.bodies.get(&entity).map(|e| e.body)
+    }
+
+    /// Build every collider again on the next step.
+    pub(crate) fn rebuild_colliders(&mut self) {
+        for entry in self.bodies.values_mut() {
+            entry.shape = None;
+        }
+    }
+
+    /// Rapier groups for colliders of an entity with `layers`.
+    pub(crate) fn groups(&self, layers: Option<&CollisionLayers>) -> InteractionGroups {
+        groups(&self.layers, self.layers.bits(layers))
+    }
+
+    /// Advance the simulation by one [`timestep`](Self::timestep).
+    pub fn step(&mut self, world: &mut World) {
+        self.sync_to_rapier(world);
+        self.rapier.gravity = to_vector(self.gravity);
+        self.rapier.integration_parameters.dt = self.timestep;
+        self.rapier.step();
+        self.sync_from_rapier(world);
+        self.move_characters(world);
+        self.update_triggers(world);
+    }
+
+    fn sync_to_rapier(&mut self, world: &World) {
+        let removed: Vec<Entity> = self
+            .bodies
+            .keys()
+            .copied()
+            .filter(|&e| !world.satisfies::<Or<&RigidBody, &Collider>>(e).unwrap_or(false))
+            .collect();
+        for entity in removed {
+            let entry = self.bodies.remove(&entity).unwrap();
+            self.rapier.remove_body(entry.body);
+        }
+
+        let mut query = world.query::<(
+            Or<&RigidBody, &Collider>,
+            Option<&Transform>,
+            Option<&Velocity>,
+            Option<&ModelPath>,
+            Option<&CollisionLayers>,
+            Option<&Trigger>,
+        )>();
+        for (entity, (components, transform, velocity, model, names, trigger)) in query.iter() {
+            let (body, collider) = components.split();
+            let desc = body.cloned().unwrap_or_else(|| RigidBody::new(BodyKind::Static));
+            let transform = transform.copied().unwrap_or_default();
+            let shape = collider.map(|c| (c.clone(), transform.scale, model.map(|m| m.0.clone())));
+            let layers = self.layers.bits(names);
+            let sensor = trigger.is_some();
+
+            if !self.bodies.contains_key(&entity) {
+                let mut builder = RigidBodyBuilder::new(body_type(desc.kind))
+                    .pose(to_pose(transform.translation, transform.rotation))
+                    .gravity_scale(desc.gravity_scale)
+                    .linear_damping(desc.linear_damping)
+                    .angular_damping(desc.angular_damping)
+                    .user_data(entity.to_bits().get() as u128);
+                if desc.lock_rotation {
+                    builder = builder.lock_rotations();
+                }
+                let body = self.rapier.bodies.insert(builder);
+                let entry = BodyEntry {
+                    body,
+                    parts: Vec::new(),
+                    desc: desc.clone(),
+                    shape: None,
+                    sensor,
+                    transform,
+                    velocity: Velocity::default(),
+                };
+                self.bodies.insert(entity, entry);
+            }
+            let entry = self.bodies.get_mut(&entity).unwrap();
+
+            let rb = &mut self.rapier.bodies[entry.body];
+            if entry.desc != desc {
+                rb.set_body_type(body_type(desc.kind), true);
+                rb.set_gravity_scale(desc.gravity_scale, true);
+                rb.set_linear_damping(desc.linear_damping);
+                rb.set_angular_damping(desc.angular_damping);
+                rb.lock_rotations(desc.lock_rotation, true);
+                entry.desc = desc;
+            }
+            if entry.transform != transform {
+                let pose = to_pose(transform.translation, transform.rotation);
+                match entry.desc.kind {
+                    BodyKind::Kinematic => rb.set_next_kinematic_position(pose),
+                    _ => rb.set_position(pose, true),
+                }
+                entry.transform = transform;
+            }
+            if let Some(&velocity) = velocity.filter(|&&v| v != entry.velocity) {
+                rb.set_linvel(to_vector(velocity.linear), true);
+                rb.set_angvel(to_vector(velocity.angular), true);
+                entry.velocity = velocity;
+            }
+
+            if entry.shape != shape || entry.sensor != sensor {
+                for part in entry.parts.drain(..) {
+                    self.rapier.remove_collider(part.handle);
+                }
+                if let Some((collider, scale, model)) = &shape {
+                    for name in names.iter().flat_map(|l| &l.0).filter(|n| self.layers.index(n).is_none()) {
+                        eprintln!("[physics] entity {}: unknown collision layer {name:?}", entity.id());
+                    }
+                    match build_colliders(&mut self.meshes, collider, *scale, model.as_deref()) {
+                        Ok((builders, mesh)) => {
+                            for (i, builder) in builders.into_iter().enumerate() {
+                                let part = mesh.as_ref().map(|m| &m[i]);
+                                let name = material_name(&self.materials, &collider.material, part);
+                                let material = name.and_then(|n| self.materials.get(n));
+                                if let (Some(name), None) = (name, material) {
+                                    eprintln!("[physics] entity {}: unknown physics material {name:?}", entity.id());
+                                }
+                                let material = material.cloned().unwrap_or_default();
+                                let part_layers = material.layer.as_deref().and_then(|layer| {
+                                    let index = self.layers.index(layer);
+                                    if index.is_none() {
+                                        eprintln!("[physics] material {name:?}: unknown collision layer {layer:?}");
+                                    }
+                                    index.map(|i| 1 << i)
+                                });
+                                let builder = builder
+                                    .friction(material.friction)
+                                    .restitution(material.restitution)
+                                    .sensor(sensor)
+                                    .collision_groups(groups(&self.layers, part_layers.unwrap_or(layers)))
+                                    .user_data(entity.to_bits().get() as u128);
+                                let handle = self.rapier.insert_collider(builder, Some(entry.body));
+                                entry.parts.push(ColliderPart { handle, layers: part_layers });
+                            }
+                        }
+                        Err(err) => eprintln!("[physics] collider of entity {}: {err:#}", entity.id()),
+                    }
+                }
+                entry.shape = shape;
+                entry.sensor = sensor;
+            }
+            // Entity layers or the layer matrix may have changed.
+            for part in &entry.parts {
+                let wanted = groups(&self.layers, part.layers.unwrap_or(layers));
+                let collider = &mut self.rapier.colliders[part.handle];
+                if collider.collision_groups() != wanted {
+                    collider.set_collision_groups(wanted);
+                }
+            }
+        }
+    }
+
+    fn sync_from_rapier(&mut self, world: &mut World) {
+        for (&entity, entry) in &mut self.bodies {
+            if entry.desc.kind != BodyKind::Dynamic {
+                continue;
+            }
+            let rb = &self.rapier.bodies[entry.body];
+            if let Ok(transform) = world.query_one_mut::<&mut Transform>(entity) {
+                transform.translation = from_vector(rb.translation());
+                transform.rotation = from_rotation(*rb.rotation());
+                entry.transform = *transform;
+            }
+            if let Ok(velocity) = world.query_one_mut::<&mut Velocity>(entity) {
+                velocity.linear = from_vector(rb.linvel());
+                velocity.angular = from_vector(rb.angvel());
+                entry.velocity = *velocity;
+            }
+        }
+    }
+}
+
+fn body_type(kind: BodyKind) -> RigidBodyType {
+    match kind {
+        BodyKind::Dynamic   => RigidBodyType::Dynamic,
+        BodyKind::Static    => RigidBodyType::Fixed,
+        BodyKind::Kinematic => RigidBodyType::KinematicPositionBased,
+    }
+}
+
+/// Collision groups of a collider on the layers in `bits`, colliding with
+/// what the matrix lets those layers interact with.
+fn groups(matrix: &LayerMatrix, bits: u32) -> InteractionGroups {
+    InteractionGroups::new(
+        Group::from_bits_truncate(bits),
+        Group::from_bits_truncate(matrix.filter(bits)),
+        InteractionTestMode::And,
+    )
+}
+
+/// Query groups that see colliders on any layer in `mask`.
+pub(crate) fn mask_groups(mask: u32) -> InteractionGroups {
+    InteractionGroups::new(Group::ALL, Group::from_bits_truncate(mask), InteractionTestMode::And)
+}
+
+/// Colliders for `collider`, and for model colliders the primitives they
+/// were built from, one per collider.
+fn build_colliders(
+    meshes: &mut HashMap<String, MeshData>,
+    collider: &Collider,
+    scale: Vec3,
+    model: Option<&str>,
+) -> Result<(Vec<ColliderBuilder>, Option<MeshData>)> {
+    let scale = scale.abs();
+    let scaled = |points: &[Vec3]| points.iter().map(|&p| to_vector(p * scale)).collect::<Vec<_>>();
+    let builder = match &collider.shape {
+        ColliderShape::Box { half_extents } => {
+            let h = *half_extents * scale;
+            ColliderBuilder::cuboid(h.x, h.y, h.z)
+        }
+        ColliderShape::Sphere { radius } => ColliderBuilder::ball(radius * scale.max_element()),
+        ColliderShape::Capsule { half_height, radius } => {
+            ColliderBuilder::capsule_y(half_height * scale.y, radius * scale.x.max(scale.z))
+        }
+        ColliderShape::ConvexHull { points } => {
+            ColliderBuilder::convex_hull(&scaled(points)).context("degenerate convex hull")?
+        }
+        ColliderShape::TriMesh { vertices, indices } => {
+            ColliderBuilder::trimesh(scaled(vertices), indices.clone()).map_err(|e| anyhow!("{e:?}"))?
+        }
+        ColliderShape::Model => {
+            let path = model.filter(|p| !p.is_empty()).context("Model collider without a ModelPath")?;
+            let mesh = match meshes.get(path) {
+                Some(mesh) => mesh.clone(),
+                None => {
+                    let geometry = load_gltf_geometry(path).with_context(|| format!("loading collision mesh {path:?}"))?;
+                    meshes.entry(path.to_owned()).or_insert(Rc::new(geometry)).clone()
+                }
+            };
+            let builders = mesh
+                .iter()
+                .map(|part| {
+                    ColliderBuilder::trimesh(scaled(&part.positions), triangles(&part.indices))
+                        .map(|builder| builder.density(collider.density))
+                        .map_err(|e| anyhow!("{e:?}"))
+                })
+                .collect::<Result<_>>()?;
+            return Ok((builders, Some(mesh)));
+        }
+    };
+    Ok((vec![builder.density(collider.density)], None))
+}
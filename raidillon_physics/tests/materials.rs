@@ -0,0 +1,91 @@
+use hecs::World;
+use raidillon_ecs::{ModelPath, Transform};
+use raidillon_physics::{Collider, ColliderShape, CollisionLayers, PhysicsConfig, PhysicsWorld};
+
+/// Two quads: material "Leaves", and "Bark" with extras naming "ice".
+const TWO_PARTS: &str = "tests/fixtures/two_materials.gltf";
+
+const CONFIG: &str = r#"(
+    layers: ["player", "foliage"],
+    ignore: [("player", "foliage")],
+    materials: {
+        "Leaves": (friction: 0.3, layer: Some("foliage")),
+        "ice": (friction: 0.02),
+    },
+)"#;
+
+fn physics() -> PhysicsWorld {
+    let config: PhysicsConfig = ron::from_str(CONFIG).unwrap();
+    let mut physics = PhysicsWorld::new(1.0 / 60.0);
+    physics.apply_config(&config).unwrap();
+    physics
+}
+
+/// Friction and membership bits of each collider of `collider`'s entity,
+/// in glTF primitive order.
+fn parts(collider: Collider) -> Vec<(f32, u32)> {
+    groups(&mut physics(), TWO_PARTS, collider)
+        .into_iter()
+        .map(|(friction, memberships, _)| (friction, memberships))
+        .collect()
+}
+
+/// Friction, membership and filter bits of each collider of a model.
+fn groups(physics: &mut PhysicsWorld, model: &str, collider: Collider) -> Vec<(f32, u32, u32)> {
+    let mut world = World::new();
+    let entity = world.spawn((Transform::default(), ModelPath(model.to_owned()), collider));
+    physics.step(&mut world);
+    physics
+        .collider_handles(entity)
+        .map(|handle| {
+            let collider = &physics.rapier().colliders[handle];
+            let groups = collider.collision_groups();
+            (collider.friction(), groups.memberships.bits(), groups.filter.bits())
+        })
+        .collect()
+}
+
+#[test]
+fn model_parts_take_materials_from_gltf() {
+    let foliage = 1 << physics().layers.index("foliage").unwrap();
+    assert_eq!(
+        parts(Collider::new(ColliderShape::Model)),
+        vec![
+            // Same name as the glTF material, and moved to its layer.
+            (0.3, foliage),
+            // Named by the glTF material's extras, on the entity's layer.
+            (0.02, 1),
+        ],
+    );
+}
+
+#[test]
+fn collider_material_overrides_gltf_materials() {
+    let collider = Collider { material: "ice".to_owned(), ..Collider::new(ColliderShape::Model) };
+    assert_eq!(parts(collider), vec![(0.02, 1), (0.02, 1)]);
+}
+
+#[test]
+fn tree_foliage_lets_the_player_through_but_not_the_trunk() {
+    let mut physics = PhysicsWorld::new(1.0 / 60.0);
+    physics.apply_config(&PhysicsConfig::load("../resources/physics.ron").unwrap()).unwrap();
+    let player = physics.layers.mask(&["player"]).unwrap();
+    let parts = groups(&mut physics, "../resources/models/tree.gltf", Collider::new(ColliderShape::Model));
+    let blocks_player: Vec<bool> = parts.iter().map(|&(_, _, filter)| filter & player != 0).collect();
+    // Bark, then leaves.
+    assert_eq!(blocks_player, vec![true, false]);
+}
+
+#[test]
+fn entity_layers_are_saved_by_name() {
+    let layers = CollisionLayers::new(&["player"]);
+    assert_eq!(ron::to_string(&layers).unwrap(), r#"["player"]"#);
+
+    let mut world = World::new();
+    let entity = world.spawn((Transform::default(), Collider::default(), layers));
+    let mut physics = physics();
+    physics.step(&mut world);
+    let handle = physics.collider_handles(entity).next().unwrap();
+    let memberships = physics.rapier().colliders[handle].collision_groups().memberships.bits();
+    assert_eq!(memberships, physics.layers.mask(&["player"]).unwrap());
+}
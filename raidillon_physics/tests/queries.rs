@@ -0,0 +1,33 @@
+use glam::Vec3;
+use hecs::World;
+use raidillon_ecs::{ModelPath, Transform};
+use raidillon_physics::{Collider, ColliderShape, PhysicsWorld, ALL_LAYERS};
+
+/// Two quads on y = 0, one glTF primitive each.
+const TWO_PARTS: &str = "tests/fixtures/two_materials.gltf";
+
+#[test]
+fn overlap_returns_multi_part_model_once() {
+    let mut world = World::new();
+    let model = world.spawn((
+        Transform::default(),
+        ModelPath(TWO_PARTS.to_owned()),
+        Collider::new(ColliderShape::Model),
+    ));
+    let ball = world.spawn((
+        Transform { translation: Vec3::new(0.0, 0.2, 0.0), ..Default::default() },
+        Collider::new(ColliderShape::Sphere { radius: 0.1 }),
+    ));
+
+    let mut physics = PhysicsWorld::new(1.0 / 60.0);
+    physics.step(&mut world);
+    assert_eq!(physics.collider_handles(model).count(), 2);
+
+    // Touches both parts of the model and the ball.
+    let mut hits = physics.overlap(Vec3::ZERO, 0.5, ALL_LAYERS);
+    assert_eq!(hits.len(), 2, "{hits:?}");
+    hits.sort();
+    let mut expected = vec![model, ball];
+    expected.sort();
+    assert_eq!(hits, expected);
+}
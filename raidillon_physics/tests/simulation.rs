@@ -0,0 +1,93 @@
+use glam::{Quat, Vec3};
+use hecs::World;
+use raidillon_ecs::Transform;
+use raidillon_physics::{BodyKind, Collider, ColliderShape, PhysicsWorld, RigidBody, Velocity};
+
+fn transform(translation: Vec3, rotation: Quat) -> Transform {
+    Transform { translation, rotation, scale: Vec3::ONE }
+}
+
+/// A static floor with its top at y = 0 and a few tumbling boxes above it.
+fn scene() -> World {
+    let mut world = World::new();
+    world.spawn((
+        transform(Vec3::new(0.0, -0.5, 0.0), Quat::IDENTITY),
+        Collider::new(ColliderShape::Box { half_extents: Vec3::new(10.0, 0.5, 10.0) }),
+    ));
+    for i in 0..3 {
+        let i = i as f32;
+        world.spawn((
+            transform(Vec3::new(i * 0.3, 1.0 + i * 1.2, 0.0), Quat::from_rotation_z(0.3 + i * 0.2)),
+            RigidBody::default(),
+            Collider::new(ColliderShape::Box { half_extents: Vec3::splat(0.5) }),
+            Velocity::default(),
+        ));
+    }
+    world
+}
+
+fn dynamic_transforms(world: &World) -> Vec<Transform> {
+    world
+        .query::<(&Transform, &RigidBody)>()
+        .iter()
+        .filter(|(_, (_, body))| body.kind == BodyKind::Dynamic)
+        .map(|(_, (transform, _))| *transform)
+        .collect()
+}
+
+fn simulate(steps: usize) -> Vec<Transform> {
+    let mut world = scene();
+    let mut physics = PhysicsWorld::new(1.0 / 60.0);
+    for _ in 0..steps {
+        physics.step(&mut world);
+    }
+    dynamic_transforms(&world)
+}
+
+#[test]
+fn same_start_gives_same_transforms() {
+    let first = simulate(120);
+    let second = simulate(120);
+    assert_eq!(first, second);
+    // The boxes actually moved.
+    assert_ne!(first, dynamic_transforms(&scene()));
+}
+
+#[test]
+fn dynamic_body_comes_to_rest_on_static_collider() {
+    let mut world = World::new();
+    world.spawn((
+        transform(Vec3::new(0.0, -0.5, 0.0), Quat::IDENTITY),
+        Collider::new(ColliderShape::Box { half_extents: Vec3::new(10.0, 0.5, 10.0) }),
+    ));
+    let body = world.spawn((
+        transform(Vec3::new(0.0, 2.0, 0.0), Quat::IDENTITY),
+        RigidBody::default(),
+        Collider::new(ColliderShape::Box { half_extents: Vec3::splat(0.5) }),
+        Velocity::default(),
+    ));
+
+    let mut physics = PhysicsWorld::new(1.0 / 60.0);
+    for _ in 0..300 {
+        physics.step(&mut world);
+    }
+
+    let transform = *world.get::<&Transform>(body).unwrap();
+    let velocity = *world.get::<&Velocity>(body).unwrap();
+    assert!((transform.translation.y - 0.5).abs() < 0.02, "resting at {}", transform.translation);
+    assert!(velocity.linear.length() < 0.01, "still moving at {}", velocity.linear);
+    assert!(velocity.angular.length() < 0.01, "still turning at {}", velocity.angular);
+}
+
+#[test]
+fn new_body_uses_its_damping() {
+    let mut world = World::new();
+    let body = world.spawn((transform(Vec3::ZERO, Quat::IDENTITY), RigidBody::default()));
+    let mut physics = PhysicsWorld::new(1.0 / 60.0);
+    physics.step(&mut world);
+
+    let handle = physics.body_handle(body).unwrap();
+    let rb = &physics.rapier().bodies[handle];
+    assert_eq!(rb.angular_damping(), RigidBody::default().angular_damping);
+    assert_eq!(rb.linear_damping(), RigidBody::default().linear_damping);
+}
@@ -1,4 +1,6 @@
-use raidillon_ecs::{Transform, ModelHandle};
+use std::collections::HashMap;
+
+use raidillon_ecs::{Transform, MeshBounds, ModelHandle, ModelPath};
 use hecs::{Entity, World};
 use crate::render::GliumRenderer;
 use crate::model::Model;
@@ -9,37 +11,36 @@ use crate::model::Model;
 pub struct ECSRenderer {
     pub renderer: GliumRenderer,
     pub world: World,
+    /// `ModelPath` last loaded (or attempted) per entity, so failures aren't
+    /// retried every frame and edited paths are picked up.
+    model_paths: HashMap<Entity, String>,
+    /// Index into `renderer.models` per loaded path, shared by every entity
+    /// with that `ModelPath`.
+    loaded_models: HashMap<String, usize>,
 }
 
 impl ECSRenderer {
     pub fn from_display_handle(handle: &crate::window::DisplayHandle) -> anyhow::Result<Self> {
         let world = World::new();
         let renderer = crate::render::GliumRenderer::new(handle.as_inner().clone())?;
-        Ok(Self { renderer, world })
+        Ok(Self::new(renderer, world))
     }
     pub fn new(renderer: GliumRenderer, world: World) -> Self {
-        Self { renderer, world }
+        Self { renderer, world, model_paths: HashMap::new(), loaded_models: HashMap::new() }
     }
 
     pub fn spawn_mesh(&mut self, model: Model, transform: Transform) -> Entity {
         let model_id = self.renderer.models.len();
+        let bounds = MeshBounds(model.mesh.geometry.aabb);
         self.renderer.models.push(model);
 
         self.world.spawn((
             transform,
             ModelHandle(model_id),
+            bounds,
         ))
     }
 
-    pub fn despawn_mesh(&mut self, entity: Entity) {
-        if let Ok(model_handle) = self.world.get::<&ModelHandle>(entity) {
-            if model_handle.0 < self.renderer.models.len() {
-                self.renderer.models.remove(model_handle.0);
-            }
-        }
-        let _ = self.world.despawn(entity);
-    }
-
     /// Render a single frame using the internal renderer & world.
     pub fn render(&mut self) {
         self.renderer.render(&self.world);
@@ -51,6 +52,63 @@ impl ECSRenderer {
         self.renderer.render_into(&self.world, target);
     }
 
+    /// Load the model of every entity whose `ModelPath` is new or changed
+    /// since the last call, and drop the `ModelHandle` of entities that lost
+    /// their `ModelPath`, along with its `MeshBounds`. Each path is loaded
+    /// once and its model shared. Returns the first load error; failed paths
+    /// are remembered and only retried once the path changes.
+    pub fn load_pending_models(&mut self) -> anyhow::Result<()> {
+        let model_paths = &self.model_paths;
+        let pending: Vec<(Entity, String)> = self
+            .world
+            .query::<&ModelPath>()
+            .iter()
+            .filter(|(e, path)| model_paths.get(e) != Some(&path.0))
+            .map(|(e, path)| (e, path.0.clone()))
+            .collect();
+
+        let world = &self.world;
+        let removed: Vec<Entity> = self
+            .model_paths
+            .keys()
+            .copied()
+            .filter(|&e| !world.satisfies::<&ModelPath>(e).unwrap_or(false))
+            .collect();
+        for entity in removed {
+            self.model_paths.remove(&entity);
+            let _ = self.world.remove_one::<ModelHandle>(entity);
+            let _ = self.world.remove_one::<MeshBounds>(entity);
+        }
+
+        let mut first_err = None;
+        for (entity, path) in pending {
+            let _ = self.world.remove_one::<ModelHandle>(entity);
+            let _ = self.world.remove_one::<MeshBounds>(entity);
+            self.model_paths.insert(entity, path.clone());
+            if path.is_empty() {
+                continue;
+            }
+            if let Some(&model_id) = self.loaded_models.get(&path) {
+                let bounds = MeshBounds(self.renderer.models[model_id].mesh.geometry.aabb);
+                let _ = self.world.insert(entity, (ModelHandle(model_id), bounds));
+                continue;
+            }
+            match crate::gltf_loader::load_gltf(&path, self.renderer.display()) {
+                Ok(model) => {
+                    let model_id = self.renderer.models.len();
+                    let bounds = MeshBounds(model.mesh.geometry.aabb);
+                    self.renderer.models.push(model);
+                    self.loaded_models.insert(path, model_id);
+                    let _ = self.world.insert(entity, (ModelHandle(model_id), bounds));
+                }
+                Err(err) => {
+                    first_err.get_or_insert(err.context(format!("loading model {path:?}")));
+                }
+            }
+        }
+        first_err.map_or(Ok(()), Err)
+    }
+
     pub fn load_mesh_from_gltf<P: AsRef<std::path::Path> + std::fmt::Debug>(
         &mut self,
         path: P,
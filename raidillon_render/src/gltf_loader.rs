@@ -1,19 +1,20 @@
 //! GPU-ready mesh loader for **glTF 2.0** (internal helper)
 //!
-//! Converts the first primitive of a glTF document into our engine `Model`.
+//! Converts the first mesh of a glTF document into our engine `Model`. Its
+//! primitives are merged into one mesh drawn with the first one's material.
 
 use anyhow::{Context, Result};
 use glium::{backend::Facade, IndexBuffer, VertexBuffer};
 use glium::index::PrimitiveType;
-use gltf::mesh::util::ReadIndices;
 use std::{fmt::Debug, path::Path};
 use crate::model::{Vertex, Mesh, Material, Model};
 use glium::texture::{RawImage2d, Texture2d, SrgbTexture2d};
 use glium::uniforms::{SamplerWrapFunction, MinifySamplerFilter, MagnifySamplerFilter};
 use gltf::image::Format as GltfFormat;
-use glam::Vec2;
+use glam::{Vec2, Vec3};
+use raidillon_core::MeshGeometry;
 
-/// Load a glTF 2.0 file from disk and upload the first primitive to the GPU.
+/// Load a glTF 2.0 file from disk and upload the first mesh to the GPU.
 pub fn load_gltf<P, F>(path: P, facade: &F) -> Result<Model>
 where
     P: AsRef<Path> + Debug,
@@ -22,7 +23,7 @@ where
     // -- parse the asset & bring buffer blobs into memory --
     let (doc, buffers, images) = gltf::import(path.as_ref()).context("failed to import glTF file")?;
 
-    // -- grab the very first mesh / primitive --
+    // -- grab the very first mesh; its first primitive gives the material --
     let mesh      = doc.meshes().next().context("glTF has no meshes")?;
     let primitive = mesh.primitives().next().context("mesh has no primitives")?;
 
@@ -113,21 +114,30 @@ where
         }
     }
 
-    // ---- Vertex/index data ----
-    let reader = primitive.reader(|buf| Some(&buffers[buf.index()].0));
+    // ---- Vertex/index data, every primitive appended ----
+    let mut vertices: Vec<Vertex> = Vec::new();
+    let mut indices:  Vec<u32>    = Vec::new();
+    for primitive in mesh.primitives() {
+        let reader = primitive.reader(|buf| Some(&buffers[buf.index()].0));
 
-    let positions: Vec<[f32; 3]> = reader.read_positions().context("missing POSITION")?.collect();
-    let normals:   Vec<[f32; 3]> = reader.read_normals().context("missing NORMAL")?.collect();
-    let tex_coords: Vec<[f32; 2]> = reader.read_tex_coords(0).map(|tc| tc.into_f32().collect()).unwrap_or_else(|| vec![[0.0, 0.0]; positions.len()]);
-    let indices:   Vec<u32> = reader.read_indices().context("missing indices")?.into_u32().collect();
+        let positions: Vec<[f32; 3]> = reader.read_positions().context("missing POSITION")?.collect();
+        let normals:   Vec<[f32; 3]> = reader.read_normals().context("missing NORMAL")?.collect();
+        let tex_coords: Vec<[f32; 2]> = reader.read_tex_coords(0).map(|tc| tc.into_f32().collect()).unwrap_or_else(|| vec![[0.0, 0.0]; positions.len()]);
 
-    // Interleave
-    let vertices: Vec<Vertex> = (0..positions.len()).map(|i| Vertex { position: positions[i], normal: normals[i], tex_coords: tex_coords[i] }).collect();
+        let base = vertices.len() as u32;
+        indices.extend(reader.read_indices().context("missing indices")?.into_u32().map(|i| base + i));
+
+        // Interleave
+        vertices.extend((0..positions.len()).map(|i| Vertex { position: positions[i], normal: normals[i], tex_coords: tex_coords[i] }));
+    }
 
     let vbuf = VertexBuffer::immutable(facade, &vertices)?;
     let ibuf = IndexBuffer ::immutable(facade, PrimitiveType::TrianglesList, &indices)?;
 
-    Ok(Model { mesh: Mesh { vbuf, ibuf }, material: mat })
+    let positions = vertices.iter().map(|v| Vec3::from(v.position)).collect();
+    let geometry  = MeshGeometry::new(positions, indices);
+
+    Ok(Model { mesh: Mesh { vbuf, ibuf, geometry }, material: mat })
 }
 
 /// Linear-space texture (RGBA8) from glTF image data.
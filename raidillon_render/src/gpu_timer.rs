@@ -0,0 +1,132 @@
+//! GPU pass timing with `GL_TIME_ELAPSED` queries.
+//!
+//! A pass passes the query from [`GpuTimers::begin`] in its draw parameters
+//! and hands it back with [`GpuTimers::end`]. Results arrive a few frames
+//! later and are forwarded to the [profiler](raidillon_core::profiler) by
+//! [`GpuTimers::collect`]. Contexts without timer queries leave passes
+//! untimed. Draw code that builds its own parameters, like the ImGui
+//! renderer, draws through a [`TimedSurface`] instead.
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use glium::backend::Facade;
+use glium::draw_parameters::TimeElapsedQuery;
+use glium::framebuffer::{MultiOutputFrameBuffer, SimpleFrameBuffer};
+use glium::uniforms::{MagnifySamplerFilter, Uniforms};
+use glium::{BlitMask, BlitTarget, DrawError, DrawParameters, Program, Rect, Surface};
+use glium::index::IndicesSource;
+use glium::vertex::MultiVerticesSource;
+use raidillon_core::profiler;
+
+/// More queries than this in flight means results are not being read back;
+/// drop the oldest instead of growing forever.
+const MAX_PENDING: usize = 64;
+
+#[derive(Default)]
+pub struct GpuTimers {
+    pending: VecDeque<(&'static str, TimeElapsedQuery)>,
+}
+
+impl GpuTimers {
+    /// A fresh query for one pass, or `None` when profiling is paused or the
+    /// context has no timer queries.
+    pub fn begin<F: Facade + ?Sized>(&self, facade: &F) -> Option<TimeElapsedQuery> {
+        if !profiler::is_enabled() {
+            return None;
+        }
+        TimeElapsedQuery::new(facade).ok()
+    }
+
+    /// Queue `query` for readback. Pass `used = false` when nothing was drawn
+    /// with it; an unused query never becomes ready.
+    pub fn end(&mut self, name: &'static str, query: Option<TimeElapsedQuery>, used: bool) {
+        let (Some(query), true) = (query, used) else {
+            return;
+        };
+        if self.pending.len() == MAX_PENDING {
+            self.pending.pop_front();
+        }
+        self.pending.push_back((name, query));
+    }
+
+    /// Report the queries that finished, in submission order.
+    pub fn collect(&mut self) {
+        while self.pending.front().is_some_and(|(_, query)| query.is_ready()) {
+            let (name, query) = self.pending.pop_front().unwrap();
+            profiler::record_gpu(name, Duration::from_nanos(query.get().into()));
+        }
+    }
+}
+
+/// Wraps a surface so every draw on it is counted in `query`.
+pub struct TimedSurface<'q, S: Surface> {
+    inner: &'q mut S,
+    query: &'q TimeElapsedQuery,
+    drawn: bool,
+}
+
+impl<'q, S: Surface> TimedSurface<'q, S> {
+    pub fn new(inner: &'q mut S, query: &'q TimeElapsedQuery) -> Self {
+        Self { inner, query, drawn: false }
+    }
+
+    /// Whether anything was drawn, for [`GpuTimers::end`].
+    pub fn drawn(&self) -> bool {
+        self.drawn
+    }
+}
+
+impl<S: Surface> Surface for TimedSurface<'_, S> {
+    fn clear(&mut self, rect: Option<&Rect>, color: Option<(f32, f32, f32, f32)>, color_srgb: bool,
+             depth: Option<f32>, stencil: Option<i32>) {
+        self.inner.clear(rect, color, color_srgb, depth, stencil);
+    }
+
+    fn get_dimensions(&self) -> (u32, u32) {
+        self.inner.get_dimensions()
+    }
+
+    fn get_depth_buffer_bits(&self) -> Option<u16> {
+        self.inner.get_depth_buffer_bits()
+    }
+
+    fn get_stencil_buffer_bits(&self) -> Option<u16> {
+        self.inner.get_stencil_buffer_bits()
+    }
+
+    fn draw<'a, 'b, V, I, U>(&mut self, vertices: V, indices: I, program: &Program, uniforms: &U,
+                             params: &DrawParameters<'_>) -> Result<(), DrawError>
+    where
+        V: MultiVerticesSource<'b>,
+        I: Into<IndicesSource<'a>>,
+        U: Uniforms,
+    {
+        let params = DrawParameters { time_elapsed_query: Some(self.query), ..params.clone() };
+        self.inner.draw(vertices, indices, program, uniforms, &params)?;
+        self.drawn = true;
+        Ok(())
+    }
+
+    fn blit_color<T: Surface>(&self, source_rect: &Rect, target: &T, target_rect: &BlitTarget,
+                              filter: MagnifySamplerFilter) {
+        self.inner.blit_color(source_rect, target, target_rect, filter);
+    }
+
+    fn blit_buffers_from_frame(&self, source_rect: &Rect, target_rect: &BlitTarget,
+                               filter: MagnifySamplerFilter, mask: BlitMask) {
+        self.inner.blit_buffers_from_frame(source_rect, target_rect, filter, mask);
+    }
+
+    fn blit_buffers_from_simple_framebuffer(&self, source: &SimpleFrameBuffer<'_>, source_rect: &Rect,
+                                            target_rect: &BlitTarget, filter: MagnifySamplerFilter,
+                                            mask: BlitMask) {
+        self.inner.blit_buffers_from_simple_framebuffer(source, source_rect, target_rect, filter, mask);
+    }
+
+    fn blit_buffers_from_multioutput_framebuffer(&self, source: &MultiOutputFrameBuffer<'_>,
+                                                 source_rect: &Rect, target_rect: &BlitTarget,
+                                                 filter: MagnifySamplerFilter, mask: BlitMask) {
+        self.inner.blit_buffers_from_multioutput_framebuffer(source, source_rect, target_rect, filter, mask);
+    }
+}
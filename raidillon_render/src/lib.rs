@@ -1,11 +1,21 @@
-pub mod camera;
+pub mod light;
 pub mod model;
 pub mod gltf_loader;
 pub mod render;
 pub mod ecs_renderer;
+pub mod picking;
+pub mod plugin;
+pub mod screenshot;
+pub mod viewport;
 pub mod window;
+pub mod gpu_timer;
 
-pub use camera::Camera;
+pub use light::DirectionalLight;
 pub use render::GliumRenderer;
 pub use ecs_renderer::ECSRenderer;
-pub use window::{DisplayHandle, init_window as init_render_window};
+pub use picking::{PickRequest, PickResult};
+pub use plugin::RenderPlugin;
+pub use screenshot::Screenshot;
+pub use viewport::Viewport;
+pub use gpu_timer::{GpuTimers, TimedSurface};
+pub use window::{DisplayHandle, WindowResized, init_window as init_render_window};
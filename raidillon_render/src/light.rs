@@ -0,0 +1,21 @@
+use glam::Vec3;
+use serde::{Deserialize, Serialize};
+
+/// Sun-like light. The first one found in the world lights the scene.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct DirectionalLight {
+    /// Direction from the light towards the scene.
+    pub direction: Vec3,
+    pub color:     Vec3,
+    pub intensity: f32,
+}
+
+impl Default for DirectionalLight {
+    fn default() -> Self {
+        Self {
+            direction: Vec3::NEG_Y,
+            color:     Vec3::ONE,
+            intensity: 1.0,
+        }
+    }
+}
@@ -1,8 +1,8 @@
-use glium::texture::{RawImage2d, SrgbTexture2d, Texture2d};
+use glium::texture::{SrgbTexture2d, Texture2d};
 use glium::uniforms::SamplerBehavior;
-use glam::{Vec2};
-use glium::{backend::Facade, implement_vertex, IndexBuffer, VertexBuffer};
-use glium::index::PrimitiveType;
+use glam::Vec2;
+use raidillon_core::MeshGeometry;
+use glium::{implement_vertex, IndexBuffer, VertexBuffer};
 
 #[derive(Copy, Clone)]
 pub struct Vertex {
@@ -15,6 +15,8 @@ implement_vertex!(Vertex, position, normal, tex_coords);
 pub struct Mesh {
     pub vbuf: VertexBuffer<Vertex>,
     pub ibuf: IndexBuffer<u32>,
+    /// CPU copy of the vertex positions and triangle indices, for picking.
+    pub geometry: MeshGeometry,
 }
 
 pub struct Material {
@@ -0,0 +1,52 @@
+//! Finding the entity under a screen position.
+//!
+//! [`ECSRenderer::pick`] casts a ray from the active camera and tests it
+//! against the bounding box, then the triangles, of every rendered model.
+//! Systems that have no access to the renderer send a [`PickRequest`] event;
+//! the window runner answers each one with a [`PickResult`].
+
+use glam::{Vec2, Vec3};
+use hecs::Entity;
+use raidillon_ecs::{Camera, ModelHandle, Transform};
+
+use crate::ecs_renderer::ECSRenderer;
+
+/// Pick at `screen` (pixels, origin top left) in a viewport `size` pixels large.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PickRequest {
+    pub screen: Vec2,
+    pub size:   Vec2,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PickResult {
+    pub request: PickRequest,
+    /// Closest entity under the cursor and the world space hit point.
+    pub hit:     Option<(Entity, Vec3)>,
+}
+
+impl ECSRenderer {
+    /// Closest model under `screen`, seen through the first [`Camera`].
+    pub fn pick(&self, screen: Vec2, size: Vec2) -> Option<(Entity, Vec3)> {
+        let camera = self.world.query::<&Camera>().iter().next().map(|(_, c)| *c)?;
+        let ray = camera.screen_ray(screen, size);
+
+        let mut closest: Option<(Entity, f32)> = None;
+        for (entity, (transform, handle)) in self.world.query::<(&Transform, &ModelHandle)>().iter() {
+            let Some(model) = self.renderer.models.get(handle.0) else {
+                continue;
+            };
+            let matrix = transform.matrix();
+            if ray.intersect_aabb(&model.mesh.geometry.aabb.transformed(&matrix)).is_none() {
+                continue;
+            }
+            let local = ray.transformed(&matrix.inverse());
+            if let Some(t) = model.mesh.geometry.raycast(&local) {
+                if closest.is_none_or(|(_, best)| t < best) {
+                    closest = Some((entity, t));
+                }
+            }
+        }
+        closest.map(|(entity, t)| (entity, ray.at(t)))
+    }
+}
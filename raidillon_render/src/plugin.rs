@@ -0,0 +1,59 @@
+use hecs::World;
+use raidillon_ecs::{App, Camera, EventReader, Events, Plugin, Resources, Stage};
+
+use crate::light::DirectionalLight;
+use crate::picking::{PickRequest, PickResult};
+use crate::screenshot::Screenshot;
+use crate::viewport::Viewport;
+use crate::window::WindowResized;
+
+/// Registers the window resize, picking and screenshot events, keeps camera
+/// aspect ratios in sync and makes cameras and lights part of saved scenes
+/// and reflection.
+pub struct RenderPlugin;
+
+impl Plugin for RenderPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<WindowResized>()
+            .add_event::<PickRequest>()
+            .add_event::<PickResult>()
+            .add_event::<Screenshot>()
+            .register_scene_component::<Camera>("Camera")
+            .register_scene_component::<DirectionalLight>("DirectionalLight")
+            .reflect::<Camera>("Camera", |c| {
+                c.insertable()
+                    .field("eye", |c| &mut c.eye)
+                    .field("center", |c| &mut c.center)
+                    .field("up", |c| &mut c.up)
+                    .field_range("fovy", 1_f32.to_radians()..=179_f32.to_radians(), |c| &mut c.fovy)
+                    .field_range("znear", 0.001..=10.0, |c| &mut c.znear)
+                    .field_range("zfar", 1.0..=100_000.0, |c| &mut c.zfar);
+            })
+            .reflect::<DirectionalLight>("DirectionalLight", |l| {
+                l.insertable()
+                    .field("direction", |l| &mut l.direction)
+                    .field_range("color", 0.0..=1.0, |l| &mut l.color)
+                    .field_range("intensity", 0.0..=100.0, |l| &mut l.intensity);
+            })
+            .add_system(Stage::PreUpdate, "camera_aspect", camera_aspect());
+    }
+}
+
+/// Keep every camera's aspect ratio in sync with the [`Viewport`] if there
+/// is one, the window otherwise.
+fn camera_aspect() -> impl FnMut(&mut World, &mut Resources) {
+    let mut reader = EventReader::<WindowResized>::default();
+    move |world, res| {
+        let events = res.fetch::<Events<WindowResized>>();
+        let resized = reader.read(&events).last().map(|sz| (sz.width as f32, sz.height as f32));
+        let size = match res.get::<Viewport>() {
+            Some(viewport) => Some((viewport.size.x, viewport.size.y)),
+            None => resized,
+        };
+        if let Some((width, height)) = size.filter(|&(_, h)| h > 0.0) {
+            for (_, cam) in world.query_mut::<&mut Camera>() {
+                cam.aspect = width / height;
+            }
+        }
+    }
+}
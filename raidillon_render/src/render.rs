@@ -1,5 +1,6 @@
-use crate::camera::Camera;
-use raidillon_ecs::{ModelHandle, Transform};
+use crate::light::DirectionalLight;
+use raidillon_ecs::{Camera, ModelHandle, Transform};
+use crate::gpu_timer::GpuTimers;
 use crate::model::{Model, Mesh};
 use glium::texture::{RawImage2d, SrgbTexture2d};
 use glium::{uniform, Program, Surface};
@@ -7,8 +8,9 @@ use glium::uniforms::{MinifySamplerFilter, MagnifySamplerFilter, SamplerWrapFunc
 use glam::{Vec3, Vec4};
 use hecs::World;
 use glium::glutin::surface::WindowSurface;
-use image::io::Reader as ImageReader;
+use image::ImageReader;
 use glium::draw_parameters::DepthTest;
+use raidillon_core::profile_scope;
 
 pub struct GliumRenderer {
     display: glium::Display<WindowSurface>,
@@ -22,6 +24,8 @@ pub struct GliumRenderer {
     skybox_program: Program,
     skybox_texture: SrgbTexture2d,
     skybox_mesh: Mesh,
+
+    gpu_timers: GpuTimers,
 }
 
 impl GliumRenderer {
@@ -67,10 +71,14 @@ impl GliumRenderer {
             skybox_program,
             skybox_texture,
             skybox_mesh,
+            gpu_timers: GpuTimers::default(),
         })
     }
 
-    fn draw_scene<S: Surface>(&self, world: &World, target: &mut S) {
+    /// Draw the opaque and sky passes. There is no shadow pass yet.
+    fn draw_scene<S: Surface>(&mut self, world: &World, target: &mut S) {
+        self.gpu_timers.collect();
+
         let cam = match world.query::<&Camera>().iter().next() {
             Some((_, cam)) => *cam,
             None => {
@@ -79,9 +87,22 @@ impl GliumRenderer {
             }
         };
 
-        // Direction from the light source (0,+Y) towards the scene.
-        let light_dir: Vec3 = Vec3::new(0.0, -1.0, 0.0).normalize();
+        let light = world
+            .query::<&DirectionalLight>()
+            .iter()
+            .next()
+            .map(|(_, light)| *light)
+            .unwrap_or_default();
+        let light_dir: Vec3 = light.direction.normalize();
+        let light_color: Vec3 = light.color * light.intensity;
 
+        let opaque_query = self.gpu_timers.begin(&self.display);
+        let params = glium::DrawParameters {
+            time_elapsed_query: opaque_query.as_ref(),
+            .. self.params.clone()
+        };
+        let mut drawn = false;
+        let opaque_scope = raidillon_core::profiler::scope("opaque");
         for (_, (tr, mh)) in world.query::<(&Transform, &ModelHandle)>().iter() {
             let model = &self.models[mh.0];
             let mesh  = &model.mesh;
@@ -101,6 +122,7 @@ impl GliumRenderer {
                 view:       cam.view().to_cols_array_2d(),
                 projection: cam.projection().to_cols_array_2d(),
                 u_light:    [light_dir.x, light_dir.y, light_dir.z],
+                u_light_color: [light_color.x, light_color.y, light_color.z],
                 tex:        sampler,
                 color:      [c[0], c[1], c[2]],
                 uv_offset:  [mat.uv_offset.x, mat.uv_offset.y],
@@ -112,11 +134,15 @@ impl GliumRenderer {
                 &mesh.ibuf,
                 &self.program,
                 &uniforms,
-                &self.params,
+                &params,
             ).unwrap();
+            drawn = true;
         }
+        drop(opaque_scope);
+        self.gpu_timers.end("opaque", opaque_query, drawn);
 
         // Render skybox
+        profile_scope!("sky");
         let mut sky_view = cam.view();
         sky_view.w_axis = Vec4::new(0.0, 0.0, 0.0, 1.0);
 
@@ -131,7 +157,9 @@ impl GliumRenderer {
             equirect: sampler,
         };
 
+        let sky_query = self.gpu_timers.begin(&self.display);
         let sky_params = glium::DrawParameters {
+            time_elapsed_query: sky_query.as_ref(),
             depth: glium::Depth {
                 test: DepthTest::IfLessOrEqual,
                 write: false,
@@ -147,6 +175,7 @@ impl GliumRenderer {
             &uniforms,
             &sky_params,
         ).unwrap();
+        self.gpu_timers.end("sky", sky_query, true);
     }
 
     pub fn render_into<S: Surface>(&mut self, world: &World, target: &mut S) {
@@ -158,6 +187,7 @@ impl GliumRenderer {
         let mut frame = self.display.draw();
         frame.clear_color_and_depth((0.1, 0.1, 0.15, 1.0), 1.0);
         self.draw_scene(world, &mut frame);
+        profile_scope!("present");
         frame.finish().unwrap();
     }
 
@@ -0,0 +1,30 @@
+//! Saving the window contents to an image file.
+//!
+//! Systems send a [`Screenshot`] event; the window runner saves the frame
+//! once it has been presented, UI included.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use glium::texture::RawImage2d;
+use image::RgbaImage;
+
+use crate::render::GliumRenderer;
+
+/// Save the next presented frame to `path`, format picked by extension.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Screenshot {
+    pub path: PathBuf,
+}
+
+impl GliumRenderer {
+    /// Write the last presented frame to `path`.
+    pub fn save_screenshot(&self, path: &Path) -> anyhow::Result<()> {
+        let raw: RawImage2d<u8> = self.display().read_front_buffer()?;
+        let mut image = RgbaImage::from_raw(raw.width, raw.height, raw.data.into_owned())
+            .context("unexpected front buffer layout")?;
+        // GL rows start at the bottom.
+        image::imageops::flip_vertical_in_place(&mut image);
+        image.save(path).with_context(|| format!("saving screenshot {path:?}"))
+    }
+}
@@ -0,0 +1,20 @@
+use glam::Vec2;
+
+/// Screen area the 3D view is drawn into, in logical pixels, when it is shown
+/// inside an editor panel instead of filling the window. Without this
+/// resource the world is rendered fullscreen.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Viewport {
+    /// Top left corner, relative to the window.
+    pub position: Vec2,
+    pub size:     Vec2,
+    /// Whether the mouse is over the viewport.
+    pub hovered:  bool,
+}
+
+impl Viewport {
+    /// `screen`, relative to the window, in viewport pixels.
+    pub fn to_local(&self, screen: Vec2) -> Vec2 {
+        screen - self.position
+    }
+}
@@ -5,6 +5,13 @@ use anyhow::Result;
 use winit::event_loop::EventLoop;
 use winit::window::Window;
 
+/// Sent whenever the window's inner size changes.
+#[derive(Copy, Clone, Debug)]
+pub struct WindowResized {
+    pub width:  u32,
+    pub height: u32,
+}
+
 #[derive(Clone)]
 pub struct DisplayHandle(Display<WindowSurface>);
 
@@ -0,0 +1,112 @@
+use glam::{Quat, Vec3};
+use hecs::{Entity, World};
+use raidillon_ecs::{App, Camera, ModelHandle, ModelPath, Name, Scene, SceneRegistry, Transform};
+use raidillon_render::{DirectionalLight, RenderPlugin};
+
+/// App with the render components registered, as the game sets it up.
+fn app() -> App {
+    let mut app = App::new();
+    app.add_plugin(RenderPlugin);
+    app
+}
+
+fn world() -> World {
+    let mut world = World::new();
+    world.spawn((
+        Name("tree".to_owned()),
+        Transform {
+            translation: Vec3::new(1.5, -0.25, 3.0),
+            rotation:    Quat::from_rotation_y(0.7),
+            scale:       Vec3::new(1.0, 2.0, 0.5),
+        },
+        ModelPath("resources/models/tree.gltf".to_owned()),
+        ModelHandle(3),
+    ));
+    world.spawn((
+        Name("camera".to_owned()),
+        Camera { eye: Vec3::new(0.0, 1.7, 5.0), fovy: 1.1, zfar: 250.0, ..Default::default() },
+    ));
+    world.spawn((
+        Name("sun".to_owned()),
+        DirectionalLight { direction: Vec3::new(-0.3, -1.0, 0.2), color: Vec3::new(1.0, 0.9, 0.8), intensity: 2.5 },
+    ));
+    world
+}
+
+fn named(world: &World, name: &str) -> Entity {
+    world
+        .query::<&Name>()
+        .iter()
+        .find(|(_, n)| n.0 == name)
+        .map(|(e, _)| e)
+        .unwrap_or_else(|| panic!("no entity named {name:?}"))
+}
+
+fn assert_same(loaded: &World) {
+    let original = world();
+    assert_eq!(loaded.len(), original.len());
+
+    let (before, after) = (named(&original, "tree"), named(loaded, "tree"));
+    assert_eq!(*loaded.get::<&Transform>(after).unwrap(), *original.get::<&Transform>(before).unwrap());
+    assert_eq!(loaded.get::<&ModelPath>(after).unwrap().0, "resources/models/tree.gltf");
+    // Models come back by path; the renderer assigns handles again.
+    assert!(loaded.get::<&ModelHandle>(after).is_err());
+
+    let (before, after) = (named(&original, "camera"), named(loaded, "camera"));
+    assert_eq!(
+        format!("{:?}", *loaded.get::<&Camera>(after).unwrap()),
+        format!("{:?}", *original.get::<&Camera>(before).unwrap()),
+    );
+
+    let (before, after) = (named(&original, "sun"), named(loaded, "sun"));
+    assert_eq!(
+        format!("{:?}", *loaded.get::<&DirectionalLight>(after).unwrap()),
+        format!("{:?}", *original.get::<&DirectionalLight>(before).unwrap()),
+    );
+}
+
+fn round_trip(to_text: fn(&Scene) -> anyhow::Result<String>, from_text: fn(&str) -> anyhow::Result<Scene>) {
+    let app = app();
+    let scenes = app.resources.fetch::<SceneRegistry>();
+    let text = to_text(&scenes.save(&world()).unwrap()).unwrap();
+
+    let mut loaded = World::new();
+    scenes.load(&mut loaded, &from_text(&text).unwrap()).unwrap();
+    assert_same(&loaded);
+}
+
+#[test]
+fn ron_round_trip() {
+    round_trip(Scene::to_ron, Scene::from_ron);
+}
+
+#[test]
+fn json_round_trip() {
+    round_trip(Scene::to_json, Scene::from_json);
+}
+
+#[test]
+fn file_round_trip_picks_format_by_extension() {
+    let app = app();
+    let scenes = app.resources.fetch::<SceneRegistry>();
+    let scene = scenes.save(&world()).unwrap();
+    for ext in ["ron", "json"] {
+        let path = std::env::temp_dir().join(format!("raidillon_scene_{}.{ext}", std::process::id()));
+        scene.save_to_file(&path).unwrap();
+        let mut loaded = World::new();
+        scenes.load(&mut loaded, &Scene::load_from_file(&path).unwrap()).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert_same(&loaded);
+    }
+}
+
+#[test]
+fn unknown_component_leaves_world_untouched() {
+    let app = app();
+    let scenes = app.resources.fetch::<SceneRegistry>();
+    let scene = Scene::from_ron(r#"(entities: [{"Name": "a"}, {"Teleporter": ()}])"#).unwrap();
+    let mut world = World::new();
+    let err = scenes.load(&mut world, &scene).unwrap_err();
+    assert!(format!("{err:#}").contains("Teleporter"), "{err:#}");
+    assert_eq!(world.len(), 0);
+}
@@ -0,0 +1,114 @@
+//! Audio mixer window.
+//!
+//! Lists the buses of the [`AudioEngine`] mixer as a tree with their volume,
+//! mute and low-pass filter. Changes are kept in [`EditorSettings`] and
+//! applied again at startup.
+
+use hecs::World;
+use imgui::{Condition, SliderFlags, Ui};
+use raidillon_audio::{AudioEngine, BusSettings};
+use raidillon_ecs::{App, Plugin, Resources};
+
+use crate::settings::EditorSettings;
+use crate::systems::UiAppExt;
+
+/// Indentation per bus tree level.
+const INDENT: f32 = 12.0;
+/// Cutoff a newly enabled low-pass filter starts at, in Hz.
+const DEFAULT_CUTOFF: f32 = 1000.0;
+
+/// Adds the audio mixer window. Does nothing without an [`AudioEngine`].
+pub struct AudioSettingsPlugin;
+
+impl Plugin for AudioSettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_startup_system(apply_saved_buses).add_ui_system(audio_window);
+    }
+}
+
+fn apply_saved_buses(_world: &mut World, res: &mut Resources) {
+    let (Some(engine), Some(settings)) = (res.get::<AudioEngine>(), res.get::<EditorSettings>()) else {
+        return;
+    };
+    let mut mixer = engine.mixer();
+    for (name, saved) in &settings.audio_buses {
+        match mixer.bus_mut(name) {
+            Some(bus) => *bus = saved.clone(),
+            None => eprintln!("[ui] no audio bus {name:?} for the saved settings"),
+        }
+    }
+}
+
+fn audio_window(ui: &Ui, _world: &mut World, res: &mut Resources) {
+    let Some(engine) = res.get::<AudioEngine>() else {
+        return;
+    };
+    // Copy the buses out so the mixer isn't locked while the window is built.
+    let buses: Vec<(String, usize, BusSettings)> = {
+        let mixer = engine.mixer();
+        mixer
+            .buses()
+            .map(|name| {
+                let depth = std::iter::successors(mixer.bus_parent(name), |&b| mixer.bus_parent(b)).count();
+                (name.to_owned(), depth, mixer.bus(name).cloned().unwrap_or_default())
+            })
+            .collect()
+    };
+
+    let mut changed = Vec::new();
+    ui.window("Audio")
+        .position([490.0, 10.0], Condition::FirstUseEver)
+        .size([300.0, 220.0], Condition::FirstUseEver)
+        .build(|| {
+            if engine.is_headless() {
+                ui.text_disabled("No audio output, sound is mixed silently");
+            }
+            for (name, depth, mut bus) in buses {
+                let _id = ui.push_id(name.as_str());
+                let indent = depth as f32 * INDENT;
+                if indent > 0.0 {
+                    ui.indent_by(indent);
+                }
+                // Checked means audible.
+                let mut audible = !bus.muted;
+                let mut edited = ui.checkbox(&name, &mut audible);
+                bus.muted = !audible;
+                ui.same_line();
+                edited |= ui.slider_config("##volume", 0.0, 1.5).display_format("%.2f").build(&mut bus.volume);
+
+                let mut filtered = bus.low_pass.is_some();
+                if ui.checkbox("Low-pass", &mut filtered) {
+                    bus.low_pass = filtered.then_some(DEFAULT_CUTOFF);
+                    edited = true;
+                }
+                if let Some(cutoff) = &mut bus.low_pass {
+                    ui.same_line();
+                    edited |= ui
+                        .slider_config("##cutoff", 50.0, 20_000.0)
+                        .display_format("%.0f Hz")
+                        .flags(SliderFlags::LOGARITHMIC)
+                        .build(cutoff);
+                }
+                if indent > 0.0 {
+                    ui.unindent_by(indent);
+                }
+                if edited {
+                    changed.push((name, bus));
+                }
+            }
+        });
+
+    if changed.is_empty() {
+        return;
+    }
+    let mut mixer = engine.mixer();
+    let mut settings = res.get_mut::<EditorSettings>();
+    for (name, bus) in changed {
+        if let Some(settings) = settings.as_mut() {
+            settings.audio_buses.insert(name.clone(), bus.clone());
+        }
+        if let Some(live) = mixer.bus_mut(&name) {
+            *live = bus;
+        }
+    }
+}
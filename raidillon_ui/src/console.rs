@@ -0,0 +1,602 @@
+//! Drop-down developer console.
+//!
+//! A console line is split into words, double quotes group words with
+//! spaces. The first word names a command or a console variable (cvar):
+//! commands get the other words as arguments, a cvar on its own prints its
+//! value and with arguments is set. Lines starting with `//` or `#` are
+//! comments.
+//!
+//! Cvars have the types of reflected fields and bind to engine settings
+//! through a getter/setter pair, see [`Console::add_cvar`]. The built-in
+//! `help`, `clear`, `echo` and `exec` are always there; [`ConsolePlugin`]
+//! adds `spawn`, `load_scene`, `screenshot` and a few cvars, and runs its
+//! autoexec file once the app has started.
+
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{bail, ensure, Context, Result};
+use glam::{EulerRot, Quat, Vec3};
+use hecs::World;
+use imgui::{
+    Condition, HistoryDirection, InputTextCallback, InputTextCallbackHandler, StyleColor, TextCallbackData, Ui,
+};
+use raidillon_ecs::{
+    App, Camera, Events, FieldType, FieldValue, ModelPath, Plugin, Reflect, ReflectRegistry, Resources, Scene, SceneRegistry, Stage,
+};
+use raidillon_input::{FPSCameraController, Input, InputFocus, InputPlugin};
+use raidillon_render::Screenshot;
+use winit::keyboard::KeyCode;
+
+use crate::history::{Command, History, Spawn};
+use crate::inspector::Selection;
+use crate::settings::EditorSettings;
+use crate::systems::UiAppExt;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum ConsoleAction {
+    Toggle,
+}
+
+/// Console command: gets the arguments, returns text to print.
+pub type CommandFn = Box<dyn FnMut(&[&str], &mut World, &mut Resources) -> Result<String>>;
+type CVarGetter = Box<dyn Fn(&World, &Resources) -> Option<FieldValue>>;
+type CVarSetter = Box<dyn Fn(&mut World, &mut Resources, FieldValue)>;
+
+struct ConsoleCommand {
+    name: &'static str,
+    help: &'static str,
+    run:  CommandFn,
+}
+
+struct CVar {
+    name: &'static str,
+    help: &'static str,
+    ty:   FieldType,
+    get:  CVarGetter,
+    set:  CVarSetter,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum LineKind {
+    Input,
+    Output,
+    Error,
+}
+
+const BUILTINS: [(&str, &str); 4] = [
+    ("help", "list commands and cvars"),
+    ("clear", "clear the console"),
+    ("echo", "print the arguments"),
+    ("exec", "run the console lines of a file"),
+];
+
+/// Nesting limit for `exec`, against files that exec themselves.
+const MAX_EXEC_DEPTH: u32 = 8;
+
+/// Commands, cvars and the state of the console window.
+#[derive(Default)]
+pub struct Console {
+    pub open:    bool,
+    commands:    Vec<ConsoleCommand>,
+    cvars:       Vec<CVar>,
+    log:         Vec<(LineKind, String)>,
+    input:       String,
+    history:     Vec<String>,
+    /// Entry recalled with the arrow keys, counted from the newest.
+    history_pos: Option<usize>,
+    exec_depth:  u32,
+    scroll_down: bool,
+}
+
+impl Console {
+    pub fn add_command<F>(&mut self, name: &'static str, help: &'static str, run: F)
+    where
+        F: FnMut(&[&str], &mut World, &mut Resources) -> Result<String> + 'static,
+    {
+        assert!(!self.has_name(name), "console name `{name}` registered twice");
+        self.commands.push(ConsoleCommand { name, help, run: Box::new(run) });
+    }
+
+    /// Add a cvar read by `get` and written by `set`. `get` returns `None`
+    /// while the bound setting does not exist, e.g. before the scene loads.
+    pub fn add_cvar<T, G, S>(&mut self, name: &'static str, help: &'static str, get: G, set: S)
+    where
+        T: Reflect + 'static,
+        G: Fn(&World, &Resources) -> Option<T> + 'static,
+        S: Fn(&mut World, &mut Resources, T) + 'static,
+    {
+        assert!(!self.has_name(name), "console name `{name}` registered twice");
+        self.cvars.push(CVar {
+            name,
+            help,
+            ty:  T::TYPE,
+            get: Box::new(move |world, res| get(world, res).map(|v| v.to_field())),
+            set: Box::new(move |world, res, value| {
+                if let Some(value) = T::from_field(value) {
+                    set(world, res, value);
+                }
+            }),
+        });
+    }
+
+    pub fn print(&mut self, text: impl Into<String>) {
+        self.push(LineKind::Output, text.into());
+    }
+
+    pub fn print_error(&mut self, text: impl Into<String>) {
+        self.push(LineKind::Error, text.into());
+    }
+
+    fn push(&mut self, kind: LineKind, text: String) {
+        for line in text.lines() {
+            self.log.push((kind, line.to_owned()));
+        }
+        self.scroll_down = true;
+    }
+
+    fn has_name(&self, name: &str) -> bool {
+        self.names().any(|n| n == name)
+    }
+
+    fn names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        BUILTINS
+            .iter()
+            .map(|(name, _)| *name)
+            .chain(self.commands.iter().map(|c| c.name))
+            .chain(self.cvars.iter().map(|c| c.name))
+    }
+
+    /// Command and cvar names starting with `prefix`, sorted.
+    pub fn completions(&self, prefix: &str) -> Vec<&'static str> {
+        let mut names: Vec<_> = self.names().filter(|n| n.starts_with(prefix)).collect();
+        names.sort_unstable();
+        names
+    }
+
+    /// Run one line. Output and errors go to the log.
+    pub fn run_line(&mut self, line: &str, world: &mut World, res: &mut Resources) {
+        if let Err(err) = self.try_run_line(line, world, res) {
+            self.print_error(format!("{err:#}"));
+        }
+    }
+
+    fn try_run_line(&mut self, line: &str, world: &mut World, res: &mut Resources) -> Result<()> {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with("//") || line.starts_with('#') {
+            return Ok(());
+        }
+        let words = split_words(line)?;
+        let words: Vec<&str> = words.iter().map(String::as_str).collect();
+        let (&name, args) = words.split_first().context("empty line")?;
+
+        match name {
+            "help" => self.help(world, res),
+            "clear" => self.log.clear(),
+            "echo" => self.print(args.join(" ")),
+            "exec" => {
+                let [path] = args else {
+                    bail!("usage: exec <file>");
+                };
+                self.exec_file(Path::new(path), world, res)?;
+            }
+            _ => {
+                if let Some(command) = self.commands.iter_mut().find(|c| c.name == name) {
+                    let output = (command.run)(args, world, res)?;
+                    if !output.is_empty() {
+                        self.print(output);
+                    }
+                } else if let Some(cvar) = self.cvars.iter().find(|c| c.name == name) {
+                    if args.is_empty() {
+                        let value = (cvar.get)(world, res).map_or("unavailable".to_owned(), |v| format_value(&v));
+                        self.print(format!("{name} = {value}"));
+                    } else {
+                        let value = parse_value(cvar.ty, args).with_context(|| format!("setting {name}"))?;
+                        (cvar.set)(world, res, value);
+                    }
+                } else {
+                    bail!("unknown command `{name}`, try `help`");
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Run every line of the file at `path`.
+    pub fn exec_file(&mut self, path: &Path, world: &mut World, res: &mut Resources) -> Result<()> {
+        ensure!(self.exec_depth < MAX_EXEC_DEPTH, "exec nested too deep at {path:?}");
+        let text = std::fs::read_to_string(path).with_context(|| format!("reading {path:?}"))?;
+        self.exec_depth += 1;
+        for line in text.lines() {
+            self.run_line(line, world, res);
+        }
+        self.exec_depth -= 1;
+        Ok(())
+    }
+
+    fn help(&mut self, world: &World, res: &Resources) {
+        let mut lines = vec!["Commands:".to_owned()];
+        let commands = BUILTINS.iter().copied().chain(self.commands.iter().map(|c| (c.name, c.help)));
+        lines.extend(commands.map(|(name, help)| format!("  {name:<16} {help}")));
+        lines.push("Cvars:".to_owned());
+        for cvar in &self.cvars {
+            let value = (cvar.get)(world, res).map_or("unavailable".to_owned(), |v| format_value(&v));
+            lines.push(format!("  {:<16} {} ({value})", cvar.name, cvar.help));
+        }
+        self.print(lines.join("\n"));
+    }
+}
+
+/// Run `line` on the [`Console`] resource, e.g. from a game system.
+pub fn execute(line: &str, world: &mut World, res: &mut Resources) {
+    // Taken out so commands can fetch any resource.
+    let Some(mut console) = res.remove::<Console>() else {
+        eprintln!("[console] no Console resource, dropping `{line}`");
+        return;
+    };
+    console.run_line(line, world, res);
+    res.insert(console);
+}
+
+/// Split on whitespace, keeping "quoted words" together.
+fn split_words(line: &str) -> Result<Vec<String>> {
+    let mut words = Vec::new();
+    let mut chars = line.chars().peekable();
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        if chars.peek().is_none() {
+            return Ok(words);
+        }
+        let mut word = String::new();
+        if chars.next_if_eq(&'"').is_some() {
+            loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some(c) => word.push(c),
+                    None => bail!("unterminated quote"),
+                }
+            }
+        } else {
+            while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+                word.push(c);
+            }
+        }
+        words.push(word);
+    }
+}
+
+fn parse_f32s<const N: usize>(args: &[&str]) -> Result<[f32; N]> {
+    ensure!(args.len() == N, "expected {N} numbers");
+    let mut out = [0.0; N];
+    for (v, arg) in out.iter_mut().zip(args) {
+        *v = arg.parse().with_context(|| format!("`{arg}` is not a number"))?;
+    }
+    Ok(out)
+}
+
+/// Parse cvar arguments. Rotations are yaw, pitch and roll in degrees, as
+/// in the inspector.
+fn parse_value(ty: FieldType, args: &[&str]) -> Result<FieldValue> {
+    Ok(match ty {
+        FieldType::Bool => match args {
+            ["1" | "true" | "on"] => FieldValue::Bool(true),
+            ["0" | "false" | "off"] => FieldValue::Bool(false),
+            _ => bail!("expected 0 or 1"),
+        },
+        FieldType::F32 => FieldValue::F32(parse_f32s::<1>(args)?[0]),
+        FieldType::Vec3 => FieldValue::Vec3(Vec3::from_array(parse_f32s(args)?)),
+        FieldType::Quat => {
+            let [yaw, pitch, roll] = parse_f32s(args)?.map(f32::to_radians);
+            FieldValue::Quat(Quat::from_euler(EulerRot::YXZ, yaw, pitch, roll))
+        }
+        FieldType::String => FieldValue::String(args.join(" ")),
+    })
+}
+
+fn format_value(value: &FieldValue) -> String {
+    match value {
+        FieldValue::Bool(v) => (*v as u8).to_string(),
+        FieldValue::F32(v) => v.to_string(),
+        FieldValue::Vec3(v) => format!("{} {} {}", v.x, v.y, v.z),
+        FieldValue::Quat(q) => {
+            let (yaw, pitch, roll) = q.to_euler(EulerRot::YXZ);
+            format!("{} {} {}", yaw.to_degrees(), pitch.to_degrees(), roll.to_degrees())
+        }
+        FieldValue::String(v) => format!("\"{v}\""),
+    }
+}
+
+pub trait ConsoleAppExt {
+    fn add_console_command<F>(&mut self, name: &'static str, help: &'static str, run: F) -> &mut Self
+    where
+        F: FnMut(&[&str], &mut World, &mut Resources) -> Result<String> + 'static;
+
+    fn add_cvar<T, G, S>(&mut self, name: &'static str, help: &'static str, get: G, set: S) -> &mut Self
+    where
+        T: Reflect + 'static,
+        G: Fn(&World, &Resources) -> Option<T> + 'static,
+        S: Fn(&mut World, &mut Resources, T) + 'static;
+}
+
+impl ConsoleAppExt for App {
+    fn add_console_command<F>(&mut self, name: &'static str, help: &'static str, run: F) -> &mut Self
+    where
+        F: FnMut(&[&str], &mut World, &mut Resources) -> Result<String> + 'static,
+    {
+        self.init_resource::<Console>();
+        self.resources.fetch_mut::<Console>().add_command(name, help, run);
+        self
+    }
+
+    fn add_cvar<T, G, S>(&mut self, name: &'static str, help: &'static str, get: G, set: S) -> &mut Self
+    where
+        T: Reflect + 'static,
+        G: Fn(&World, &Resources) -> Option<T> + 'static,
+        S: Fn(&mut World, &mut Resources, T) + 'static,
+    {
+        self.init_resource::<Console>();
+        self.resources.fetch_mut::<Console>().add_cvar(name, help, get, set);
+        self
+    }
+}
+
+/// Adds the console window, toggled with the key left of 1.
+#[derive(Default)]
+pub struct ConsolePlugin {
+    /// Console lines run before the first frame, after the startup systems.
+    pub autoexec: Option<PathBuf>,
+}
+
+impl ConsolePlugin {
+    pub fn with_autoexec(path: impl Into<PathBuf>) -> Self {
+        Self { autoexec: Some(path.into()) }
+    }
+}
+
+impl Plugin for ConsolePlugin {
+    fn build(&self, app: &mut App) {
+        let mut input = Input::<ConsoleAction>::new();
+        input.map_key(KeyCode::Backquote, ConsoleAction::Toggle);
+
+        app.add_plugin(InputPlugin::<ConsoleAction>::global())
+            .insert_resource(input)
+            .init_resource::<InputFocus>()
+            .add_console_command("spawn", "spawn [model]: spawn an entity, optionally with a model", spawn)
+            .add_console_command("load_scene", "load_scene <file>: replace the world with a scene", load_scene)
+            .add_console_command("screenshot", "screenshot [file]: save the window to an image", screenshot)
+            .add_cvar(
+                "fov",
+                "vertical field of view of the camera, degrees",
+                |world, _| world.query::<&Camera>().iter().next().map(|(_, c)| c.fovy.to_degrees()),
+                |world, _, fov: f32| {
+                    for (_, camera) in world.query_mut::<&mut Camera>() {
+                        camera.fovy = fov.clamp(1.0, 179.0).to_radians();
+                    }
+                },
+            )
+            .add_cvar(
+                "cam_speed",
+                "fly camera speed, units per second",
+                |_, res| res.get::<FPSCameraController>().map(|c| c.speed),
+                |_, res, speed: f32| {
+                    if let Some(mut controller) = res.get_mut::<FPSCameraController>() {
+                        controller.speed = speed.max(0.0);
+                    }
+                },
+            )
+            .add_cvar(
+                "cam_sensitivity",
+                "fly camera mouse sensitivity, degrees per pixel",
+                |_, res| res.get::<FPSCameraController>().map(|c| c.sensitivity),
+                |_, res, sensitivity: f32| {
+                    if let Some(mut controller) = res.get_mut::<FPSCameraController>() {
+                        controller.sensitivity = sensitivity.max(0.0);
+                    }
+                },
+            )
+            .add_ui_system(console_window);
+
+        if let Some(path) = self.autoexec.clone() {
+            let mut pending = Some(path);
+            app.add_system(Stage::PreUpdate, "console_autoexec", move |world, res| {
+                let Some(path) = pending.take() else {
+                    return;
+                };
+                let Some(mut console) = res.remove::<Console>() else {
+                    return;
+                };
+                if let Err(err) = console.exec_file(&path, world, res) {
+                    console.print_error(format!("autoexec: {err:#}"));
+                }
+                res.insert(console);
+            });
+        }
+    }
+}
+
+fn spawn(args: &[&str], world: &mut World, res: &mut Resources) -> Result<String> {
+    let model = match args {
+        [] => None,
+        [path] => Some(path.to_string()),
+        _ => bail!("usage: spawn [model]"),
+    };
+    let registry = res.fetch::<ReflectRegistry>();
+    let mut spawn = Spawn::new(world);
+    let entity = spawn.entity;
+    match res.get_mut::<History>() {
+        Some(mut history) => history.execute(spawn, world, &registry),
+        None => spawn.apply(world, &registry),
+    }
+    if let Some(model) = model {
+        world.insert_one(entity, ModelPath(model))?;
+    }
+    if let Some(mut selection) = res.get_mut::<Selection>() {
+        selection.0 = Some(entity);
+    }
+    Ok(format!("spawned entity {}", entity.id()))
+}
+
+fn load_scene(args: &[&str], world: &mut World, res: &mut Resources) -> Result<String> {
+    let [path] = args else {
+        bail!("usage: load_scene <file>");
+    };
+    let scene = Scene::load_from_file(path)?;
+    world.clear();
+    let spawned = res.fetch::<SceneRegistry>().load(world, &scene)?;
+
+    if let Some(mut history) = res.get_mut::<History>() {
+        history.clear();
+    }
+    if let Some(mut selection) = res.get_mut::<Selection>() {
+        selection.0 = None;
+    }
+    if let Some(mut settings) = res.get_mut::<EditorSettings>() {
+        settings.last_scene = Some(path.into());
+    }
+    Ok(format!("loaded {} entities from {path}", spawned.len()))
+}
+
+fn screenshot(args: &[&str], _world: &mut World, res: &mut Resources) -> Result<String> {
+    let path = match args {
+        [] => {
+            let secs = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+            PathBuf::from(format!("screenshot-{secs}.png"))
+        }
+        [path] => PathBuf::from(path),
+        _ => bail!("usage: screenshot [file]"),
+    };
+    let output = format!("saving {path:?}");
+    res.fetch_mut::<Events<Screenshot>>().send(Screenshot { path });
+    Ok(output)
+}
+
+/// Arrow key history and tab completion for the input line.
+struct InputCallbacks<'a> {
+    names:       Vec<&'static str>,
+    history:     &'a [String],
+    history_pos: &'a mut Option<usize>,
+    log:         &'a mut Vec<(LineKind, String)>,
+}
+
+impl InputTextCallbackHandler for InputCallbacks<'_> {
+    /// Drop the toggle key's character.
+    fn char_filter(&mut self, c: char) -> Option<char> {
+        (c != '`').then_some(c)
+    }
+
+    fn on_completion(&mut self, mut data: TextCallbackData) {
+        let prefix = data.str().to_owned();
+        if prefix.contains(char::is_whitespace) {
+            return;
+        }
+        let matches: Vec<&str> = self.names.iter().copied().filter(|n| n.starts_with(&prefix)).collect();
+        let Some(first) = matches.first() else {
+            return;
+        };
+        let common = matches.iter().fold(*first, |common, name| {
+            let len = common.chars().zip(name.chars()).take_while(|(a, b)| a == b).count();
+            &common[..len]
+        });
+        data.clear();
+        if matches.len() == 1 {
+            data.push_str(&format!("{common} "));
+        } else {
+            data.push_str(common);
+            self.log.push((LineKind::Output, matches.join("  ")));
+        }
+    }
+
+    fn on_history(&mut self, dir: HistoryDirection, mut data: TextCallbackData) {
+        let len = self.history.len();
+        if len == 0 {
+            return;
+        }
+        *self.history_pos = match (dir, *self.history_pos) {
+            (HistoryDirection::Up, None) => Some(0),
+            (HistoryDirection::Up, Some(pos)) => Some((pos + 1).min(len - 1)),
+            (HistoryDirection::Down, None | Some(0)) => None,
+            (HistoryDirection::Down, Some(pos)) => Some(pos - 1),
+        };
+        data.clear();
+        if let Some(pos) = *self.history_pos {
+            data.push_str(&self.history[len - 1 - pos]);
+        }
+    }
+}
+
+fn console_window(ui: &Ui, world: &mut World, res: &mut Resources) {
+    let Some(mut console) = res.remove::<Console>() else {
+        return;
+    };
+    let toggled = res.fetch::<Input<ConsoleAction>>().action_pressed(ConsoleAction::Toggle);
+    if toggled {
+        console.open = !console.open;
+    }
+    if console.open {
+        draw_console(ui, &mut console, toggled, world, res);
+        // Keep typed keys away from the game.
+        res.fetch_mut::<InputFocus>().focused = false;
+    }
+    res.insert(console);
+}
+
+fn draw_console(ui: &Ui, console: &mut Console, just_opened: bool, world: &mut World, res: &mut Resources) {
+    let [width, height] = ui.io().display_size;
+    let mut submitted = None;
+    ui.window("Console")
+        .position([0.0, 0.0], Condition::Always)
+        .size([width, height * 0.4], Condition::Always)
+        .title_bar(false)
+        .movable(false)
+        .resizable(false)
+        .collapsible(false)
+        .build(|| {
+            let log_height = -ui.frame_height_with_spacing();
+            ui.child_window("console_log").size([0.0, log_height]).build(|| {
+                for (kind, line) in &console.log {
+                    let color = match kind {
+                        LineKind::Input  => [0.6, 0.8, 1.0, 1.0],
+                        LineKind::Output => ui.style_color(StyleColor::Text),
+                        LineKind::Error  => [1.0, 0.4, 0.4, 1.0],
+                    };
+                    ui.text_colored(color, line);
+                }
+                if std::mem::take(&mut console.scroll_down) {
+                    ui.set_scroll_here_y_with_ratio(1.0);
+                }
+            });
+
+            if just_opened {
+                ui.set_keyboard_focus_here();
+            }
+            let names = console.completions("");
+            let callbacks = InputCallbacks {
+                names,
+                history:     &console.history,
+                history_pos: &mut console.history_pos,
+                log:         &mut console.log,
+            };
+            ui.set_next_item_width(-1.0);
+            let entered = ui
+                .input_text("##console_input", &mut console.input)
+                .enter_returns_true(true)
+                .callback(
+                    InputTextCallback::COMPLETION | InputTextCallback::HISTORY | InputTextCallback::CHAR_FILTER,
+                    callbacks,
+                )
+                .build();
+            if entered {
+                submitted = Some(std::mem::take(&mut console.input));
+                ui.set_keyboard_focus_here_with_offset(imgui::FocusedWidget::Previous);
+            }
+        });
+
+    if let Some(line) = submitted {
+        console.push(LineKind::Input, format!("> {line}"));
+        if !line.trim().is_empty() && console.history.last() != Some(&line) {
+            console.history.push(line.clone());
+        }
+        console.history_pos = None;
+        console.run_line(&line, world, res);
+    }
+}
@@ -0,0 +1,352 @@
+//! Translate, rotate and scale gizmos for the selected entity.
+//!
+//! Handles are drawn on the ImGui background draw list, projected with the
+//! first [`Camera`] in the world. Hovering and dragging cast the mouse ray
+//! against the handles: axis lines for translate/scale, rings for rotate.
+//! Finished drags are recorded in the [`History`] as one step each. Clicking
+//! anywhere else in the viewport selects the entity under the cursor.
+
+use glam::{Quat, Vec2, Vec3};
+use hecs::{Entity, World};
+use imgui::{Condition, MouseButton, Ui};
+use raidillon_core::Ray;
+use raidillon_ecs::{App, Camera, EventReader, Events, Plugin, Resources, Transform};
+use raidillon_render::{PickRequest, PickResult, Viewport};
+
+use crate::history::{History, SetComponent};
+use crate::inspector::{InspectorPlugin, Selection};
+use crate::systems::UiAppExt;
+
+/// Handle length as a fraction of the distance to the camera, so the gizmo
+/// keeps a constant size on screen.
+const HANDLE_SCALE: f32 = 0.15;
+/// How close, relative to the handle length, the mouse ray must pass.
+const PICK_TOLERANCE: f32 = 0.08;
+const RING_SEGMENTS: usize = 64;
+
+const AXIS_COLORS: [[f32; 4]; 3] = [
+    [0.9, 0.2, 0.2, 1.0],
+    [0.2, 0.8, 0.2, 1.0],
+    [0.2, 0.4, 0.9, 1.0],
+];
+const ACTIVE_COLOR: [f32; 4] = [1.0, 0.85, 0.1, 1.0];
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum GizmoMode {
+    Translate,
+    Rotate,
+    Scale,
+}
+
+/// Axes the gizmo works along. Scaling always uses the local axes.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum GizmoSpace {
+    Local,
+    World,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct GizmoSettings {
+    pub mode:           GizmoMode,
+    pub space:          GizmoSpace,
+    pub snap:           bool,
+    pub translate_snap: f32,
+    /// Degrees.
+    pub rotate_snap:    f32,
+    pub scale_snap:     f32,
+}
+
+impl Default for GizmoSettings {
+    fn default() -> Self {
+        Self {
+            mode:           GizmoMode::Translate,
+            space:          GizmoSpace::World,
+            snap:           false,
+            translate_snap: 0.25,
+            rotate_snap:    15.0,
+            scale_snap:     0.1,
+        }
+    }
+}
+
+/// Adds the gizmo, its settings window and click selection.
+pub struct GizmoPlugin;
+
+impl Plugin for GizmoPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugin(InspectorPlugin)
+            .init_resource::<GizmoSettings>()
+            .add_ui_system(gizmo_system());
+    }
+}
+
+struct ActiveDrag {
+    entity: Entity,
+    mode:   GizmoMode,
+    /// World space direction of the dragged axis, fixed for the whole drag.
+    axis:   Vec3,
+    /// Local axis index, used by scaling.
+    index:  usize,
+    start:  Transform,
+    /// Position along `axis` (translate/scale) or in the ring plane (rotate)
+    /// where the drag started, relative to the gizmo origin.
+    grab:   Vec3,
+}
+
+fn gizmo_system() -> impl FnMut(&Ui, &mut World, &mut Resources) {
+    let mut active: Option<ActiveDrag> = None;
+    let mut picks = EventReader::<PickResult>::default();
+    move |ui, world, res| {
+        settings_window(ui, &mut res.fetch_mut::<GizmoSettings>());
+
+        if let Some(result) = picks.read(&res.fetch::<Events<PickResult>>()).last() {
+            res.fetch_mut::<Selection>().0 = result.hit.map(|(entity, _)| entity);
+        }
+
+        let view = ViewRect::current(ui, res);
+        let on_gizmo = update_gizmo(ui, world, res, &view, &mut active);
+        if !on_gizmo && view.mouse_free && ui.is_mouse_clicked(MouseButton::Left) {
+            res.fetch_mut::<Events<PickRequest>>().send(PickRequest {
+                screen: view.mouse(ui),
+                size:   view.size,
+            });
+        }
+    }
+}
+
+/// Where the 3D view is on screen: the [`Viewport`] panel if there is one,
+/// the whole window otherwise.
+struct ViewRect {
+    position:   Vec2,
+    size:       Vec2,
+    /// Whether clicks at the mouse position reach the 3D view.
+    mouse_free: bool,
+    in_panel:   bool,
+}
+
+impl ViewRect {
+    fn current(ui: &Ui, res: &Resources) -> Self {
+        match res.get::<Viewport>() {
+            Some(viewport) => Self {
+                position:   viewport.position,
+                size:       viewport.size,
+                mouse_free: viewport.hovered,
+                in_panel:   true,
+            },
+            None => Self {
+                position:   Vec2::ZERO,
+                size:       Vec2::from(ui.io().display_size),
+                mouse_free: !ui.io().want_capture_mouse,
+                in_panel:   false,
+            },
+        }
+    }
+
+    /// Mouse position in view pixels.
+    fn mouse(&self, ui: &Ui) -> Vec2 {
+        Vec2::from(ui.io().mouse_pos) - self.position
+    }
+}
+
+/// Drag and draw the gizmo of the selected entity. Returns whether the mouse
+/// is on one of its handles.
+fn update_gizmo(ui: &Ui, world: &mut World, res: &mut Resources, view: &ViewRect, active: &mut Option<ActiveDrag>) -> bool {
+    let selected = res.fetch::<Selection>().0;
+    let Some((entity, transform)) = selected.and_then(|e| world.get::<&Transform>(e).ok().map(|t| (e, *t))) else {
+        *active = None;
+        return false;
+    };
+    let Some(camera) = world.query::<&Camera>().iter().next().map(|(_, c)| *c) else {
+        return false;
+    };
+    let settings = *res.fetch::<GizmoSettings>();
+    let ray = camera.screen_ray(view.mouse(ui), view.size);
+    let origin = transform.translation;
+    let length = (origin - camera.eye).length() * HANDLE_SCALE;
+    let handles = axes(&transform, &settings);
+
+    if active.as_ref().is_some_and(|d| d.entity != entity || d.mode != settings.mode) {
+        *active = None;
+    }
+    if let Some(drag) = active.as_ref() {
+        if ui.is_mouse_down(MouseButton::Left) {
+            if let Some(updated) = apply_drag(drag, &ray, origin, length, &settings) {
+                if let Ok(current) = world.query_one_mut::<&mut Transform>(entity) {
+                    *current = updated;
+                }
+            }
+        } else {
+            let after = world.get::<&Transform>(entity).ok().map(|t| *t);
+            if let Some(after) = after.filter(|t| *t != drag.start) {
+                let mut history = res.fetch_mut::<History>();
+                history.record(SetComponent { entity, before: drag.start, after });
+                history.seal();
+            }
+            *active = None;
+        }
+    }
+
+    let hovered = match active.as_ref() {
+        Some(drag) => Some(drag.index),
+        None if view.mouse_free => pick_handle(&ray, settings.mode, origin, &handles, length),
+        None => None,
+    };
+    if let (None, Some(index)) = (active.as_ref(), hovered) {
+        if ui.is_mouse_clicked(MouseButton::Left) {
+            let axis = handles[index];
+            let grab = match settings.mode {
+                GizmoMode::Rotate => ray.intersect_plane(origin, axis).map(|t| ray.at(t) - origin),
+                _ => ray.closest_to_line(origin, axis).map(|(_, t)| axis * t),
+            };
+            *active = grab.map(|grab| ActiveDrag {
+                entity,
+                mode: settings.mode,
+                axis,
+                index,
+                start: transform,
+                grab,
+            });
+        }
+    }
+
+    let transform = world.get::<&Transform>(entity).map(|t| *t).unwrap_or(transform);
+    draw(ui, &camera, view, settings.mode, transform.translation, &axes(&transform, &settings), length, hovered);
+    hovered.is_some()
+}
+
+fn settings_window(ui: &Ui, settings: &mut GizmoSettings) {
+    ui.window("Gizmo")
+        .position([260.0, 10.0], Condition::FirstUseEver)
+        .size([220.0, 170.0], Condition::FirstUseEver)
+        .build(|| {
+            ui.radio_button("Translate", &mut settings.mode, GizmoMode::Translate);
+            ui.same_line();
+            ui.radio_button("Rotate", &mut settings.mode, GizmoMode::Rotate);
+            ui.same_line();
+            ui.radio_button("Scale", &mut settings.mode, GizmoMode::Scale);
+            ui.radio_button("World", &mut settings.space, GizmoSpace::World);
+            ui.same_line();
+            ui.radio_button("Local", &mut settings.space, GizmoSpace::Local);
+            ui.checkbox("Snap", &mut settings.snap);
+            ui.input_float("Move step", &mut settings.translate_snap).build();
+            ui.input_float("Angle step", &mut settings.rotate_snap).build();
+            ui.input_float("Scale step", &mut settings.scale_snap).build();
+        });
+}
+
+/// World space directions of the gizmo's X, Y and Z handles.
+fn axes(transform: &Transform, settings: &GizmoSettings) -> [Vec3; 3] {
+    let rotation = match (settings.mode, settings.space) {
+        (GizmoMode::Scale, _) | (_, GizmoSpace::Local) => transform.rotation,
+        (_, GizmoSpace::World) => Quat::IDENTITY,
+    };
+    [rotation * Vec3::X, rotation * Vec3::Y, rotation * Vec3::Z]
+}
+
+/// Index of the handle closest to `ray`, if any is within reach.
+fn pick_handle(ray: &Ray, mode: GizmoMode, origin: Vec3, axes: &[Vec3; 3], length: f32) -> Option<usize> {
+    let tolerance = length * PICK_TOLERANCE;
+    let mut best: Option<(usize, f32)> = None;
+    for (index, &axis) in axes.iter().enumerate() {
+        let distance = match mode {
+            GizmoMode::Rotate => ray
+                .intersect_plane(origin, axis)
+                .map(|t| ((ray.at(t) - origin).length() - length).abs()),
+            GizmoMode::Translate | GizmoMode::Scale => ray
+                .closest_to_line(origin, axis)
+                .filter(|&(s, t)| s > 0.0 && (0.0..=length).contains(&t))
+                .map(|(s, t)| ray.at(s).distance(origin + axis * t)),
+        };
+        if let Some(distance) = distance.filter(|&d| d < tolerance) {
+            if best.is_none_or(|(_, d)| distance < d) {
+                best = Some((index, distance));
+            }
+        }
+    }
+    best.map(|(index, _)| index)
+}
+
+/// Transform resulting from dragging to where `ray` points, or `None` if the
+/// ray no longer meets the handle's line or plane.
+fn apply_drag(drag: &ActiveDrag, ray: &Ray, origin: Vec3, length: f32, settings: &GizmoSettings) -> Option<Transform> {
+    let mut transform = drag.start;
+    match drag.mode {
+        GizmoMode::Translate => {
+            let (_, t) = ray.closest_to_line(drag.start.translation, drag.axis)?;
+            let delta = snap(t - drag.grab.dot(drag.axis), settings.snap.then_some(settings.translate_snap));
+            transform.translation = drag.start.translation + drag.axis * delta;
+        }
+        GizmoMode::Scale => {
+            let (_, t) = ray.closest_to_line(origin, drag.axis)?;
+            let factor = 1.0 + (t - drag.grab.dot(drag.axis)) / length;
+            let scale = snap(drag.start.scale[drag.index] * factor, settings.snap.then_some(settings.scale_snap));
+            transform.scale[drag.index] = scale.max(0.0001);
+        }
+        GizmoMode::Rotate => {
+            let current = ray.at(ray.intersect_plane(origin, drag.axis)?) - origin;
+            let angle = drag.axis.dot(drag.grab.cross(current)).atan2(drag.grab.dot(current));
+            let angle = snap(angle.to_degrees(), settings.snap.then_some(settings.rotate_snap)).to_radians();
+            transform.rotation = (Quat::from_axis_angle(drag.axis, angle) * drag.start.rotation).normalize();
+        }
+    }
+    Some(transform)
+}
+
+fn snap(value: f32, step: Option<f32>) -> f32 {
+    match step {
+        Some(step) if step > 0.0 => (value / step).round() * step,
+        _ => value,
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn draw(
+    ui: &Ui,
+    camera: &Camera,
+    view: &ViewRect,
+    mode: GizmoMode,
+    origin: Vec3,
+    axes: &[Vec3; 3],
+    length: f32,
+    highlighted: Option<usize>,
+) {
+    // Over the viewport panel's image, under the other windows otherwise.
+    let draw_list = if view.in_panel { ui.get_foreground_draw_list() } else { ui.get_background_draw_list() };
+    let project = |p: Vec3| camera.world_to_screen(p, view.size).map(|s| (s + view.position).to_array());
+    let Some(center) = project(origin) else {
+        return;
+    };
+
+    draw_list.with_clip_rect(view.position.to_array(), (view.position + view.size).to_array(), || {
+        for (index, &axis) in axes.iter().enumerate() {
+            let color = if highlighted == Some(index) { ACTIVE_COLOR } else { AXIS_COLORS[index] };
+            match mode {
+                GizmoMode::Translate | GizmoMode::Scale => {
+                    let Some(tip) = project(origin + axis * length) else {
+                        continue;
+                    };
+                    draw_list.add_line(center, tip, color).thickness(3.0).build();
+                    if mode == GizmoMode::Translate {
+                        draw_list.add_circle(tip, 6.0, color).filled(true).build();
+                    } else {
+                        let [x, y] = tip;
+                        draw_list.add_rect([x - 5.0, y - 5.0], [x + 5.0, y + 5.0], color).filled(true).build();
+                    }
+                }
+                GizmoMode::Rotate => {
+                    let u = axis.any_orthonormal_vector() * length;
+                    let v = axis.cross(u);
+                    let ring: Option<Vec<[f32; 2]>> = (0..=RING_SEGMENTS)
+                        .map(|i| {
+                            let a = i as f32 / RING_SEGMENTS as f32 * std::f32::consts::TAU;
+                            project(origin + u * a.cos() + v * a.sin())
+                        })
+                        .collect();
+                    if let Some(ring) = ring {
+                        draw_list.add_polyline(ring, color).thickness(2.5).build();
+                    }
+                }
+            }
+        }
+    });
+}
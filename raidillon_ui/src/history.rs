@@ -0,0 +1,377 @@
+//! Undo/redo for editor operations.
+//!
+//! Every edit made through the editor panels goes through the [`History`]
+//! resource as a [`Command`]. Consecutive commands that continue the same
+//! edit, like the frames of a slider drag, are merged into one step until the
+//! history is [sealed](History::seal).
+//!
+//! Despawned entities are brought back under their old [`Entity`] handle, so
+//! older commands referring to them stay valid. Only components known to the
+//! [`ReflectRegistry`] survive the round trip.
+
+use std::any::Any;
+
+use hecs::{Component, Entity, World};
+use imgui::Ui;
+use raidillon_ecs::{App, FieldValue, Name, Parent, Plugin, ReflectRegistry, Resources, Transform};
+use raidillon_input::{Input, InputPlugin};
+use winit::keyboard::{KeyCode, ModifiersState};
+
+use crate::systems::UiAppExt;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum EditorAction {
+    Undo,
+    Redo,
+}
+
+/// A reversible edit of the world.
+pub trait Command: Any {
+    /// Perform the edit, or perform it again after an undo.
+    fn apply(&mut self, world: &mut World, registry: &ReflectRegistry);
+    fn undo(&mut self, world: &mut World, registry: &ReflectRegistry);
+
+    /// Fold `next`, recorded right after this command, into it. Returns
+    /// `false` when the two can't be merged.
+    fn merge(&mut self, _next: &dyn Command) -> bool {
+        false
+    }
+}
+
+#[derive(Default)]
+pub struct History {
+    undo: Vec<Box<dyn Command>>,
+    redo: Vec<Box<dyn Command>>,
+    /// Whether the newest command may still absorb the next one.
+    open: bool,
+}
+
+impl History {
+    /// Apply `command` and record it.
+    pub fn execute(&mut self, mut command: impl Command, world: &mut World, registry: &ReflectRegistry) {
+        command.apply(world, registry);
+        self.record(command);
+    }
+
+    /// Record a command whose effect is already in the world.
+    pub fn record(&mut self, command: impl Command) {
+        self.redo.clear();
+        if self.open {
+            if let Some(last) = self.undo.last_mut() {
+                if last.merge(&command) {
+                    return;
+                }
+            }
+        }
+        self.undo.push(Box::new(command));
+        self.open = true;
+    }
+
+    /// Stop merging into the newest command, e.g. when a drag ends.
+    pub fn seal(&mut self) {
+        self.open = false;
+    }
+
+    pub fn undo(&mut self, world: &mut World, registry: &ReflectRegistry) -> bool {
+        self.open = false;
+        let Some(mut command) = self.undo.pop() else {
+            return false;
+        };
+        command.undo(world, registry);
+        self.redo.push(command);
+        true
+    }
+
+    pub fn redo(&mut self, world: &mut World, registry: &ReflectRegistry) -> bool {
+        self.open = false;
+        let Some(mut command) = self.redo.pop() else {
+            return false;
+        };
+        command.apply(world, registry);
+        self.undo.push(command);
+        true
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+        self.open = false;
+    }
+}
+
+/// Adds [`History`] with Ctrl+Z / Ctrl+Shift+Z bound to undo and redo.
+pub struct HistoryPlugin;
+
+impl Plugin for HistoryPlugin {
+    fn build(&self, app: &mut App) {
+        let mut input = Input::<EditorAction>::new();
+        input.map_chord(ModifiersState::CONTROL, KeyCode::KeyZ, EditorAction::Undo);
+        input.map_chord(ModifiersState::CONTROL | ModifiersState::SHIFT, KeyCode::KeyZ, EditorAction::Redo);
+
+        app.add_plugin(InputPlugin::<EditorAction>::global())
+            .insert_resource(input)
+            .init_resource::<History>()
+            .add_ui_system(undo_redo);
+    }
+}
+
+fn undo_redo(ui: &Ui, world: &mut World, res: &mut Resources) {
+    // Text fields have their own undo.
+    if ui.io().want_text_input {
+        return;
+    }
+    let input = res.fetch::<Input<EditorAction>>();
+    let registry = res.fetch::<ReflectRegistry>();
+    let mut history = res.fetch_mut::<History>();
+    if input.action_pressed(EditorAction::Undo) {
+        history.undo(world, &registry);
+    } else if input.action_pressed(EditorAction::Redo) {
+        history.redo(world, &registry);
+    }
+}
+
+/// Write a reflected field.
+pub struct SetField {
+    pub entity:    Entity,
+    pub component: &'static str,
+    pub field:     &'static str,
+    pub before:    FieldValue,
+    pub after:     FieldValue,
+}
+
+impl SetField {
+    fn write(&self, world: &World, registry: &ReflectRegistry, value: &FieldValue) {
+        if let Some(field) = registry.get(self.component).and_then(|c| c.field(self.field)) {
+            field.set(world, self.entity, value.clone());
+        }
+    }
+}
+
+impl Command for SetField {
+    fn apply(&mut self, world: &mut World, registry: &ReflectRegistry) {
+        self.write(world, registry, &self.after);
+    }
+
+    fn undo(&mut self, world: &mut World, registry: &ReflectRegistry) {
+        self.write(world, registry, &self.before);
+    }
+
+    fn merge(&mut self, next: &dyn Command) -> bool {
+        let Some(next) = (next as &dyn Any).downcast_ref::<Self>() else {
+            return false;
+        };
+        let same = (next.entity, next.component, next.field) == (self.entity, self.component, self.field);
+        if same {
+            self.after = next.after.clone();
+        }
+        same
+    }
+}
+
+/// Replace a whole component value, e.g. a `Transform` moved by a gizmo.
+pub struct SetComponent<T> {
+    pub entity: Entity,
+    pub before: T,
+    pub after:  T,
+}
+
+impl<T: Component + Clone> Command for SetComponent<T> {
+    fn apply(&mut self, world: &mut World, _registry: &ReflectRegistry) {
+        if let Ok(component) = world.query_one_mut::<&mut T>(self.entity) {
+            *component = self.after.clone();
+        }
+    }
+
+    fn undo(&mut self, world: &mut World, _registry: &ReflectRegistry) {
+        if let Ok(component) = world.query_one_mut::<&mut T>(self.entity) {
+            *component = self.before.clone();
+        }
+    }
+
+    fn merge(&mut self, next: &dyn Command) -> bool {
+        let Some(next) = (next as &dyn Any).downcast_ref::<Self>() else {
+            return false;
+        };
+        let same = next.entity == self.entity;
+        if same {
+            self.after = next.after.clone();
+        }
+        same
+    }
+}
+
+/// Add the default value of a reflected component.
+pub struct InsertComponent {
+    pub entity:    Entity,
+    pub component: &'static str,
+}
+
+impl Command for InsertComponent {
+    fn apply(&mut self, world: &mut World, registry: &ReflectRegistry) {
+        if let Some(info) = registry.get(self.component) {
+            info.insert_default(world, self.entity);
+        }
+    }
+
+    fn undo(&mut self, world: &mut World, registry: &ReflectRegistry) {
+        if let Some(info) = registry.get(self.component) {
+            info.remove(world, self.entity);
+        }
+    }
+}
+
+/// Remove a reflected component, keeping it for undo.
+pub struct RemoveComponent {
+    pub entity:    Entity,
+    pub component: &'static str,
+    saved:         Option<Box<dyn Any>>,
+}
+
+impl RemoveComponent {
+    pub fn new(entity: Entity, component: &'static str) -> Self {
+        Self { entity, component, saved: None }
+    }
+}
+
+impl Command for RemoveComponent {
+    fn apply(&mut self, world: &mut World, registry: &ReflectRegistry) {
+        if let Some(info) = registry.get(self.component) {
+            self.saved = info.take(world, self.entity);
+        }
+    }
+
+    fn undo(&mut self, world: &mut World, registry: &ReflectRegistry) {
+        if let (Some(info), Some(saved)) = (registry.get(self.component), self.saved.take()) {
+            info.restore(world, self.entity, saved);
+        }
+    }
+}
+
+/// Move an entity under another one, or back to the root with `None`.
+pub struct Reparent {
+    pub entity: Entity,
+    pub before: Option<Entity>,
+    pub after:  Option<Entity>,
+}
+
+impl Reparent {
+    /// Move `entity` from its current parent to `parent`.
+    pub fn new(world: &World, entity: Entity, parent: Option<Entity>) -> Self {
+        let before = world.get::<&Parent>(entity).ok().map(|p| p.0);
+        Self { entity, before, after: parent }
+    }
+
+    fn set(&self, world: &mut World, parent: Option<Entity>) {
+        match parent {
+            Some(parent) => {
+                let _ = world.insert_one(self.entity, Parent(parent));
+            }
+            None => {
+                let _ = world.remove_one::<Parent>(self.entity);
+            }
+        }
+    }
+}
+
+impl Command for Reparent {
+    fn apply(&mut self, world: &mut World, _registry: &ReflectRegistry) {
+        self.set(world, self.after);
+    }
+
+    fn undo(&mut self, world: &mut World, _registry: &ReflectRegistry) {
+        self.set(world, self.before);
+    }
+}
+
+/// Reflected components of a despawned entity.
+type Snapshot = Vec<(&'static str, Box<dyn Any>)>;
+
+fn take_entity(world: &mut World, registry: &ReflectRegistry, entity: Entity) -> Snapshot {
+    let snapshot = registry
+        .components()
+        .filter_map(|info| Some((info.name, info.take(world, entity)?)))
+        .collect();
+    let _ = world.despawn(entity);
+    snapshot
+}
+
+fn restore_entity(world: &mut World, registry: &ReflectRegistry, entity: Entity, snapshot: Snapshot) {
+    world.spawn_at(entity, ());
+    for (name, value) in snapshot {
+        if let Some(info) = registry.get(name) {
+            info.restore(world, entity, value);
+        }
+    }
+}
+
+/// Spawn a new entity with a [`Name`] and an identity [`Transform`].
+pub struct Spawn {
+    pub entity: Entity,
+    saved:      Option<Snapshot>,
+}
+
+impl Spawn {
+    /// Reserve the handle up front so callers can select the new entity.
+    pub fn new(world: &World) -> Self {
+        Self { entity: world.reserve_entity(), saved: None }
+    }
+}
+
+impl Command for Spawn {
+    fn apply(&mut self, world: &mut World, registry: &ReflectRegistry) {
+        match self.saved.take() {
+            Some(snapshot) => restore_entity(world, registry, self.entity, snapshot),
+            None => world.spawn_at(self.entity, (Name("Entity".to_owned()), Transform::default())),
+        }
+    }
+
+    fn undo(&mut self, world: &mut World, registry: &ReflectRegistry) {
+        self.saved = Some(take_entity(world, registry, self.entity));
+    }
+}
+
+/// Despawn an entity, keeping its reflected components for undo. Its
+/// children move to the root and are put back under it on undo.
+pub struct Despawn {
+    pub entity: Entity,
+    saved:      Snapshot,
+    children:   Vec<Entity>,
+}
+
+impl Despawn {
+    pub fn new(entity: Entity) -> Self {
+        Self { entity, saved: Vec::new(), children: Vec::new() }
+    }
+}
+
+impl Command for Despawn {
+    fn apply(&mut self, world: &mut World, registry: &ReflectRegistry) {
+        self.children = world
+            .query::<&Parent>()
+            .iter()
+            .filter(|(_, parent)| parent.0 == self.entity)
+            .map(|(child, _)| child)
+            .collect();
+        for &child in &self.children {
+            let _ = world.remove_one::<Parent>(child);
+        }
+        self.saved = take_entity(world, registry, self.entity);
+    }
+
+    fn undo(&mut self, world: &mut World, registry: &ReflectRegistry) {
+        restore_entity(world, registry, self.entity, std::mem::take(&mut self.saved));
+        for &child in &self.children {
+            if world.contains(child) {
+                let _ = world.insert_one(child, Parent(self.entity));
+            }
+        }
+    }
+}
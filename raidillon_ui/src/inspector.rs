@@ -0,0 +1,210 @@
+//! Entity list and component inspector panels.
+//!
+//! The "Entities" window lists every entity in the world and can spawn or
+//! despawn them. The "Inspector" window shows the components of the selected
+//! entity that are known to the [`ReflectRegistry`], with a widget per field,
+//! and lets it be moved under another entity. All edits go through the
+//! [`History`] so they can be undone.
+
+use glam::{EulerRot, Quat, Vec3};
+use hecs::{Entity, World};
+use imgui::{Condition, Drag, TreeNodeFlags, Ui};
+use raidillon_ecs::{App, FieldInfo, FieldValue, Name, Parent, Plugin, ReflectRegistry, Resources};
+
+use crate::history::{
+    Despawn, History, HistoryPlugin, InsertComponent, RemoveComponent, Reparent, SetField, Spawn,
+};
+use crate::systems::UiAppExt;
+
+/// Entity currently shown in the inspector.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Selection(pub Option<Entity>);
+
+/// Adds the entity list and inspector windows.
+pub struct InspectorPlugin;
+
+impl Plugin for InspectorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugin(HistoryPlugin)
+            .init_resource::<Selection>()
+            .add_ui_system(entities_panel)
+            .add_ui_system(inspector_panel);
+    }
+}
+
+/// `Name` of the entity, or its id when it has none.
+pub fn entity_label(world: &World, entity: Entity) -> String {
+    match world.get::<&Name>(entity) {
+        Ok(name) if !name.0.is_empty() => name.0.clone(),
+        _ => format!("Entity {}", entity.id()),
+    }
+}
+
+fn entities_panel(ui: &Ui, world: &mut World, res: &mut Resources) {
+    let registry = res.fetch::<ReflectRegistry>();
+    let mut history = res.fetch_mut::<History>();
+    let mut selection = res.fetch_mut::<Selection>();
+    if selection.0.is_some_and(|e| !world.contains(e)) {
+        selection.0 = None;
+    }
+
+    ui.window("Entities")
+        .position([10.0, 10.0], Condition::FirstUseEver)
+        .size([240.0, 320.0], Condition::FirstUseEver)
+        .build(|| {
+            if ui.button("Spawn") {
+                let spawn = Spawn::new(world);
+                selection.0 = Some(spawn.entity);
+                history.execute(spawn, world, &registry);
+            }
+            ui.same_line();
+            ui.enabled(selection.0.is_some(), || {
+                if ui.button("Despawn") {
+                    if let Some(entity) = selection.0.take() {
+                        history.execute(Despawn::new(entity), world, &registry);
+                    }
+                }
+            });
+            ui.separator();
+
+            let mut entities: Vec<Entity> = world.iter().map(|e| e.entity()).collect();
+            entities.sort_by_key(|e| e.id());
+            for entity in entities {
+                let label = format!("{}##{}", entity_label(world, entity), entity.to_bits());
+                if ui.selectable_config(label).selected(selection.0 == Some(entity)).build() {
+                    selection.0 = Some(entity);
+                }
+            }
+        });
+}
+
+fn inspector_panel(ui: &Ui, world: &mut World, res: &mut Resources) {
+    let selected = res.fetch::<Selection>().0;
+    let registry = res.fetch::<ReflectRegistry>();
+    let mut history = res.fetch_mut::<History>();
+
+    ui.window("Inspector")
+        .position([10.0, 340.0], Condition::FirstUseEver)
+        .size([320.0, 360.0], Condition::FirstUseEver)
+        .build(|| {
+            let Some(entity) = selected.filter(|&e| world.contains(e)) else {
+                ui.text_disabled("No entity selected");
+                return;
+            };
+            ui.text(entity_label(world, entity));
+            parent_combo(ui, world, &registry, &mut history, entity);
+            ui.separator();
+
+            let mut removed = None;
+            let present: Vec<_> = registry.components().filter(|c| c.has(world, entity)).collect();
+            for info in present {
+                let _id = ui.push_id(info.name);
+                if !ui.collapsing_header(info.name, TreeNodeFlags::DEFAULT_OPEN) {
+                    continue;
+                }
+                for field in &info.fields {
+                    let Some(before) = field.get(world, entity) else {
+                        continue;
+                    };
+                    let mut after = before.clone();
+                    if edit_field(ui, field, &mut after) {
+                        let edit = SetField { entity, component: info.name, field: field.name, before, after };
+                        history.execute(edit, world, &registry);
+                    }
+                    if ui.is_item_deactivated() {
+                        history.seal();
+                    }
+                }
+                if ui.small_button("Remove component") {
+                    removed = Some(info);
+                }
+            }
+            if let Some(info) = removed {
+                history.execute(RemoveComponent::new(entity, info.name), world, &registry);
+            }
+
+            ui.separator();
+            if let Some(_combo) = ui.begin_combo("##add", "Add component") {
+                for info in registry.components() {
+                    if info.can_insert() && !info.has(world, entity) && ui.selectable(info.name) {
+                        history.execute(InsertComponent { entity, component: info.name }, world, &registry);
+                    }
+                }
+            }
+        });
+}
+
+/// Whether `ancestor` is `entity` or one of its parents.
+fn is_ancestor(world: &World, ancestor: Entity, entity: Entity) -> bool {
+    let mut current = Some(entity);
+    // Bounded in case a cycle was made outside the editor.
+    for _ in 0..=world.len() {
+        match current {
+            Some(e) if e == ancestor => return true,
+            Some(e) => current = world.get::<&Parent>(e).ok().map(|p| p.0),
+            None => return false,
+        }
+    }
+    false
+}
+
+/// Pick the parent of `entity` among the entities that don't descend from it.
+fn parent_combo(ui: &Ui, world: &mut World, registry: &ReflectRegistry, history: &mut History, entity: Entity) {
+    let parent = world.get::<&Parent>(entity).ok().map(|p| p.0);
+    let preview = parent.map_or_else(|| "None".to_owned(), |p| entity_label(world, p));
+    let Some(_combo) = ui.begin_combo("Parent", preview) else {
+        return;
+    };
+    let mut choice = None;
+    if ui.selectable_config("None").selected(parent.is_none()).build() {
+        choice = Some(None);
+    }
+    let mut candidates: Vec<Entity> = world
+        .iter()
+        .map(|e| e.entity())
+        .filter(|&e| !is_ancestor(world, entity, e))
+        .collect();
+    candidates.sort_by_key(|e| e.id());
+    for candidate in candidates {
+        let label = format!("{}##{}", entity_label(world, candidate), candidate.to_bits());
+        if ui.selectable_config(label).selected(parent == Some(candidate)).build() {
+            choice = Some(Some(candidate));
+        }
+    }
+    if let Some(after) = choice.filter(|&after| after != parent) {
+        history.execute(Reparent::new(world, entity, after), world, registry);
+    }
+}
+
+/// Widget for a single reflected field. Rotations are edited as Euler angles
+/// in degrees. Returns `true` when `value` was changed.
+pub fn edit_field(ui: &Ui, field: &FieldInfo, value: &mut FieldValue) -> bool {
+    match value {
+        FieldValue::Bool(v) => ui.checkbox(field.name, v),
+        FieldValue::F32(v) => match &field.range {
+            Some(range) => ui.slider(field.name, *range.start(), *range.end(), v),
+            None => Drag::new(field.name).speed(0.01).build(ui, v),
+        },
+        FieldValue::Vec3(v) => {
+            let mut drag = Drag::new(field.name).speed(0.01);
+            if let Some(range) = &field.range {
+                drag = drag.range(*range.start(), *range.end());
+            }
+            let mut xyz = v.to_array();
+            let changed = drag.build_array(ui, &mut xyz);
+            *v = Vec3::from(xyz);
+            changed
+        }
+        FieldValue::Quat(q) => {
+            let (yaw, pitch, roll) = q.to_euler(EulerRot::YXZ);
+            let mut degrees = [yaw.to_degrees(), pitch.to_degrees(), roll.to_degrees()];
+            let changed = Drag::new(field.name).speed(0.5).build_array(ui, &mut degrees);
+            let [yaw, pitch, roll] = degrees.map(f32::to_radians);
+            if changed {
+                *q = Quat::from_euler(EulerRot::YXZ, yaw, pitch, roll);
+            }
+            changed
+        }
+        FieldValue::String(s) => ui.input_text(field.name, s).build(),
+    }
+}
@@ -1,3 +1,23 @@
+pub mod audio;
+pub mod console;
+pub mod gizmo;
+pub mod history;
+pub mod inspector;
+pub mod plugin;
+pub mod profiler;
+pub mod settings;
+pub mod systems;
 pub mod ui;
+pub mod viewport;
 
-pub use ui::Gui;
+pub use audio::AudioSettingsPlugin;
+pub use console::{Console, ConsoleAction, ConsoleAppExt, ConsolePlugin};
+pub use gizmo::{GizmoMode, GizmoPlugin, GizmoSettings, GizmoSpace};
+pub use history::{Command, EditorAction, History, HistoryPlugin};
+pub use inspector::{InspectorPlugin, Selection};
+pub use plugin::UiPlugin;
+pub use profiler::{ProfilerAction, ProfilerOverlay, ProfilerPlugin};
+pub use settings::{EditorSettings, EditorSettingsPlugin};
+pub use systems::{UiAppExt, UiSystems};
+pub use ui::{Gui, GuiConfig};
+pub use viewport::{ViewportPlugin, ViewportTexture};
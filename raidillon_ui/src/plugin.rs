@@ -0,0 +1,15 @@
+use raidillon_ecs::{App, Plugin};
+
+use crate::systems::UiSystems;
+use crate::ui::GuiConfig;
+
+/// Enables the Dear ImGui overlay. Without it the window runner draws the
+/// world only.
+pub struct UiPlugin;
+
+impl Plugin for UiPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<UiSystems>()
+            .init_resource::<GuiConfig>();
+    }
+}
@@ -0,0 +1,205 @@
+//! Profiler overlay.
+//!
+//! Shows the frames recorded by [`raidillon_core::profiler`]: a frame time
+//! graph to pick a frame from, the GPU pass times and a flame view of the
+//! CPU scopes of the picked frame. F3 toggles the window.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use hecs::World;
+use imgui::{Condition, MouseButton, Ui};
+use raidillon_core::profiler::{self, FrameProfile};
+use raidillon_ecs::{App, Plugin, Resources};
+use raidillon_input::{Input, InputPlugin};
+use winit::keyboard::KeyCode;
+
+use crate::systems::UiAppExt;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum ProfilerAction {
+    Toggle,
+}
+
+/// State of the profiler window.
+pub struct ProfilerOverlay {
+    pub open:       bool,
+    /// Frame shown in the flame view, the newest one when unset.
+    pub selected:   Option<u64>,
+    /// Where "Export trace" writes the Chrome trace.
+    pub trace_path: PathBuf,
+}
+
+impl Default for ProfilerOverlay {
+    fn default() -> Self {
+        Self { open: true, selected: None, trace_path: "trace.json".into() }
+    }
+}
+
+/// Adds the profiler window, toggled with F3.
+pub struct ProfilerPlugin;
+
+impl Plugin for ProfilerPlugin {
+    fn build(&self, app: &mut App) {
+        let mut input = Input::<ProfilerAction>::new();
+        input.map_key(KeyCode::F3, ProfilerAction::Toggle);
+
+        app.add_plugin(InputPlugin::<ProfilerAction>::global())
+            .insert_resource(input)
+            .init_resource::<ProfilerOverlay>()
+            .add_ui_system(profiler_window);
+    }
+}
+
+const GRAPH_HEIGHT: f32 = 60.0;
+const ROW_HEIGHT: f32 = 18.0;
+/// Reference line in the frame graph, one frame at 60 Hz.
+const TARGET_MS: f32 = 1000.0 / 60.0;
+
+fn ms(duration: Duration) -> f32 {
+    duration.as_secs_f32() * 1000.0
+}
+
+fn profiler_window(ui: &Ui, _world: &mut World, res: &mut Resources) {
+    let mut overlay = res.fetch_mut::<ProfilerOverlay>();
+    if res.fetch::<Input<ProfilerAction>>().action_pressed(ProfilerAction::Toggle) {
+        overlay.open = !overlay.open;
+    }
+    if !overlay.open {
+        return;
+    }
+
+    let mut open = true;
+    ui.window("Profiler")
+        .opened(&mut open)
+        .position([10.0, 480.0], Condition::FirstUseEver)
+        .size([600.0, 300.0], Condition::FirstUseEver)
+        .build(|| {
+            let frames: Vec<FrameProfile> = profiler::with_history(|h| h.iter().cloned().collect());
+            let Some(newest) = frames.last() else {
+                ui.text("No frames recorded yet");
+                return;
+            };
+
+            let average = frames.iter().map(|f| ms(f.duration)).sum::<f32>() / frames.len() as f32;
+            ui.text(format!(
+                "Frame {:.2} ms, average {:.2} ms ({:.0} fps)",
+                ms(newest.duration),
+                average,
+                1000.0 / average
+            ));
+
+            let mut paused = !profiler::is_enabled();
+            if ui.checkbox("Pause", &mut paused) {
+                profiler::set_enabled(!paused);
+            }
+            ui.same_line();
+            if ui.button("Latest") {
+                overlay.selected = None;
+            }
+            ui.same_line();
+            if ui.button("Export trace") {
+                let path = &overlay.trace_path;
+                match std::fs::write(path, profiler::chrome_trace(&frames)) {
+                    Ok(()) => eprintln!("[profiler] wrote {path:?}"),
+                    Err(err) => eprintln!("[profiler] writing {path:?}: {err}"),
+                }
+            }
+
+            frame_graph(ui, &frames, &mut overlay.selected);
+
+            let frame = overlay
+                .selected
+                .and_then(|index| frames.iter().find(|f| f.index == index))
+                .unwrap_or(newest);
+            ui.text(format!("Frame {}: {:.2} ms", frame.index, ms(frame.duration)));
+            if frame.gpu.is_empty() {
+                ui.text_disabled("GPU: no results");
+            } else {
+                let passes: Vec<String> = frame.gpu.iter().map(|g| format!("{} {:.2} ms", g.name, ms(g.duration))).collect();
+                ui.text(format!("GPU: {}", passes.join(", ")));
+            }
+            flame_view(ui, frame);
+        });
+    overlay.open &= open;
+}
+
+/// Bar per frame, scaled to the slowest one. Clicking a bar selects it.
+fn frame_graph(ui: &Ui, frames: &[FrameProfile], selected: &mut Option<u64>) {
+    let origin = ui.cursor_screen_pos();
+    let width = ui.content_region_avail()[0].max(1.0);
+    ui.invisible_button("frame_graph", [width, GRAPH_HEIGHT]);
+    let hovered = ui.is_item_hovered();
+
+    let max_ms = frames.iter().map(|f| ms(f.duration)).fold(TARGET_MS * 1.5, f32::max);
+    let bar = width / profiler::HISTORY_LEN as f32;
+    let bottom = origin[1] + GRAPH_HEIGHT;
+    let mouse = ui.io().mouse_pos;
+
+    let draw_list = ui.get_window_draw_list();
+    draw_list
+        .add_rect(origin, [origin[0] + width, bottom], [0.1, 0.1, 0.1, 1.0])
+        .filled(true)
+        .build();
+    for (i, frame) in frames.iter().enumerate() {
+        let frame_ms = ms(frame.duration);
+        let x = origin[0] + i as f32 * bar;
+        let top = bottom - frame_ms / max_ms * GRAPH_HEIGHT;
+        let under_mouse = hovered && (x..x + bar).contains(&mouse[0]);
+        let color = if *selected == Some(frame.index) || under_mouse {
+            [1.0, 1.0, 1.0, 1.0]
+        } else if frame_ms > TARGET_MS {
+            [0.9, 0.4, 0.2, 1.0]
+        } else {
+            [0.3, 0.7, 0.3, 1.0]
+        };
+        draw_list.add_rect([x, top], [x + bar.max(1.0), bottom], color).filled(true).build();
+
+        if under_mouse {
+            ui.tooltip_text(format!("Frame {}: {:.2} ms", frame.index, frame_ms));
+            if ui.is_mouse_clicked(MouseButton::Left) {
+                *selected = Some(frame.index);
+            }
+        }
+    }
+    let target = bottom - TARGET_MS / max_ms * GRAPH_HEIGHT;
+    draw_list
+        .add_line([origin[0], target], [origin[0] + width, target], [1.0, 1.0, 1.0, 0.3])
+        .build();
+}
+
+/// CPU scopes of `frame` laid out on a time axis, one row per nesting level.
+fn flame_view(ui: &Ui, frame: &FrameProfile) {
+    let rows = frame.scopes.iter().map(|s| s.depth + 1).max().unwrap_or(1);
+    let origin = ui.cursor_screen_pos();
+    let width = ui.content_region_avail()[0].max(1.0);
+    let height = rows as f32 * ROW_HEIGHT;
+    ui.invisible_button("flame_view", [width, height]);
+    let hovered = ui.is_item_hovered();
+
+    let total = frame.duration.as_secs_f32().max(f32::EPSILON);
+    let mouse = ui.io().mouse_pos;
+    let draw_list = ui.get_window_draw_list();
+    for scope in &frame.scopes {
+        let x0 = origin[0] + scope.start.as_secs_f32() / total * width;
+        let x1 = (x0 + scope.duration.as_secs_f32() / total * width).max(x0 + 1.0);
+        let y0 = origin[1] + scope.depth as f32 * ROW_HEIGHT;
+        let y1 = y0 + ROW_HEIGHT - 1.0;
+
+        draw_list.add_rect([x0, y0], [x1, y1], scope_color(scope.name)).filled(true).build();
+        let label = ui.calc_text_size(scope.name);
+        if label[0] + 4.0 < x1 - x0 {
+            draw_list.add_text([x0 + 2.0, y0 + 1.0], [0.0, 0.0, 0.0, 1.0], scope.name);
+        }
+        if hovered && (x0..x1).contains(&mouse[0]) && (y0..y1).contains(&mouse[1]) {
+            ui.tooltip_text(format!("{}: {:.3} ms", scope.name, ms(scope.duration)));
+        }
+    }
+}
+
+/// Stable pastel color per scope name.
+fn scope_color(name: &str) -> [f32; 4] {
+    let hash = name.bytes().fold(2166136261u32, |h, b| (h ^ b as u32).wrapping_mul(16777619));
+    let channel = |shift: u32| 0.55 + ((hash >> shift) & 0xff) as f32 / 255.0 * 0.4;
+    [channel(0), channel(8), channel(16), 1.0]
+}
@@ -0,0 +1,103 @@
+//! Opt-in persistence of the ImGui layout and editor preferences.
+//!
+//! [`EditorSettingsPlugin`] picks a per-user config directory, points the
+//! ImGui ini file there and loads [`EditorSettings`] from `editor.ron`. The
+//! settings are written back when the app shuts down.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use raidillon_audio::BusSettings;
+use raidillon_ecs::{App, Plugin};
+use serde::{Deserialize, Serialize};
+
+use crate::plugin::UiPlugin;
+use crate::ui::GuiConfig;
+
+/// Preferences kept between editor sessions.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EditorSettings {
+    pub camera_speed:       f32,
+    pub camera_sensitivity: f32,
+    /// Scene that was open when the editor was last closed.
+    pub last_scene:         Option<PathBuf>,
+    /// Audio buses changed in the audio window, by name.
+    pub audio_buses:        BTreeMap<String, BusSettings>,
+}
+
+impl Default for EditorSettings {
+    fn default() -> Self {
+        Self {
+            camera_speed:       3.0,
+            camera_sensitivity: 0.1,
+            last_scene:         None,
+            audio_buses:        BTreeMap::new(),
+        }
+    }
+}
+
+impl EditorSettings {
+    /// Read settings from `path`. A missing file gives the defaults.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let text = std::fs::read_to_string(path).with_context(|| format!("reading settings {path:?}"))?;
+        ron::from_str(&text).with_context(|| format!("parsing settings {path:?}"))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let text = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())?;
+        std::fs::write(path, text).with_context(|| format!("writing settings {path:?}"))
+    }
+}
+
+/// Persists the ImGui layout and [`EditorSettings`] under
+/// `<config dir>/<app_name>`. Without this plugin nothing is written to disk
+/// and [`EditorSettings`] start from their defaults.
+pub struct EditorSettingsPlugin {
+    pub app_name: String,
+}
+
+impl EditorSettingsPlugin {
+    pub fn new(app_name: &str) -> Self {
+        Self { app_name: app_name.to_owned() }
+    }
+}
+
+impl Plugin for EditorSettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugin(UiPlugin);
+
+        let dir = dirs::config_dir()
+            .context("no config directory on this platform")
+            .map(|dir| dir.join(&self.app_name))
+            .and_then(|dir| {
+                std::fs::create_dir_all(&dir).with_context(|| format!("creating {dir:?}"))?;
+                Ok(dir)
+            });
+        let dir = match dir {
+            Ok(dir) => dir,
+            Err(err) => {
+                eprintln!("[ui] editor settings won't be saved: {err:#}");
+                app.init_resource::<EditorSettings>();
+                return;
+            }
+        };
+
+        let path = dir.join("editor.ron");
+        let settings = EditorSettings::load(&path).unwrap_or_else(|err| {
+            eprintln!("[ui] {err:#}");
+            EditorSettings::default()
+        });
+        app.resources.fetch_mut::<GuiConfig>().ini_path = Some(dir.join("imgui.ini"));
+        app.insert_resource(settings)
+            .add_shutdown_system(move |_world, res| {
+                if let Err(err) = res.fetch::<EditorSettings>().save(&path) {
+                    eprintln!("[ui] {err:#}");
+                }
+            });
+    }
+}
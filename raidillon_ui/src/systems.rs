@@ -0,0 +1,37 @@
+use hecs::World;
+use imgui::Ui;
+use raidillon_ecs::{App, Resources};
+
+use crate::plugin::UiPlugin;
+
+pub type UiSystem = Box<dyn FnMut(&Ui, &mut World, &mut Resources)>;
+
+/// UI callbacks run every redraw, in registration order, while the ImGui
+/// frame is being built.
+#[derive(Default)]
+pub struct UiSystems(pub Vec<UiSystem>);
+
+impl UiSystems {
+    pub fn run(&mut self, ui: &Ui, world: &mut World, resources: &mut Resources) {
+        for system in &mut self.0 {
+            system(ui, world, resources);
+        }
+    }
+}
+
+pub trait UiAppExt {
+    fn add_ui_system<F>(&mut self, system: F) -> &mut Self
+    where
+        F: FnMut(&Ui, &mut World, &mut Resources) + 'static;
+}
+
+impl UiAppExt for App {
+    fn add_ui_system<F>(&mut self, system: F) -> &mut Self
+    where
+        F: FnMut(&Ui, &mut World, &mut Resources) + 'static,
+    {
+        self.add_plugin(UiPlugin);
+        self.resources.fetch_mut::<UiSystems>().0.push(Box::new(system));
+        self
+    }
+}
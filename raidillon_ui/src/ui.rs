@@ -1,12 +1,30 @@
+use std::path::PathBuf;
+use std::rc::Rc;
 use std::time::Instant;
 
 use anyhow::Result;
-use imgui::{Context as ImguiContext, Ui};
+use imgui::{Context as ImguiContext, TextureId, Ui};
 use imgui_winit_support::{HiDpiMode, WinitPlatform};
-use imgui_glium_renderer::Renderer as ImguiGliumRenderer;
+use imgui_glium_renderer::{Renderer as ImguiGliumRenderer, Texture};
 use winit::window::Window;
-use glium::Frame;
-use raidillon_render::{DisplayHandle, ECSRenderer};
+use glium::backend::{Context, Facade};
+use glium::framebuffer::{DepthRenderBuffer, SimpleFrameBuffer};
+use glium::texture::{DepthFormat, Texture2d};
+use glium::uniforms::SamplerBehavior;
+use glium::{Frame, Surface};
+use raidillon_core::profile_scope;
+use raidillon_render::{DisplayHandle, ECSRenderer, GpuTimers, TimedSurface};
+
+/// Options for creating the [`Gui`]. Read from the resources by the window
+/// runner; the default keeps nothing between runs.
+#[derive(Clone, Debug, Default)]
+pub struct GuiConfig {
+    /// Where ImGui loads and saves window layout.
+    pub ini_path: Option<PathBuf>,
+    /// Let panels dock into each other and the window edges. Needs the
+    /// `docking` feature.
+    pub docking:  bool,
+}
 
 /// Convenience wrapper that owns all ImGui state required for integration with
 /// winit + glium.
@@ -15,12 +33,31 @@ pub struct Gui {
     platform:   WinitPlatform,
     renderer:   ImguiGliumRenderer,
     last_frame: Instant,
+    viewport:   Option<ViewportTarget>,
+    context:    Rc<Context>,
+    gpu_timers: GpuTimers,
+}
+
+/// Offscreen target the world is drawn into when shown in a panel.
+struct ViewportTarget {
+    id:      TextureId,
+    texture: Rc<Texture2d>,
+    depth:   DepthRenderBuffer,
+    size:    (u32, u32),
 }
 
 impl Gui {
-    pub fn new(display: &DisplayHandle, window: &Window) -> Result<Self> {
+    pub fn new(display: &DisplayHandle, window: &Window, config: &GuiConfig) -> Result<Self> {
         let mut imgui = ImguiContext::create();
-        imgui.set_ini_filename(None);
+        imgui.set_ini_filename(config.ini_path.clone());
+        #[cfg(feature = "docking")]
+        if config.docking {
+            imgui.io_mut().config_flags |= imgui::ConfigFlags::DOCKING_ENABLE;
+        }
+        #[cfg(not(feature = "docking"))]
+        if config.docking {
+            eprintln!("[ui] docking requested but raidillon_ui was built without the `docking` feature");
+        }
         let mut platform = WinitPlatform::new(&mut imgui);
         platform.attach_window(imgui.io_mut(), window, HiDpiMode::Default);
         imgui.fonts().add_font(&[imgui::FontSource::DefaultFontData { config: None }]);
@@ -31,6 +68,9 @@ impl Gui {
             platform,
             renderer,
             last_frame: Instant::now(),
+            viewport: None,
+            context: Rc::clone(display.as_inner().get_context()),
+            gpu_timers: GpuTimers::default(),
         })
     }
 
@@ -61,17 +101,37 @@ impl Gui {
     where
         F: FnOnce(&Ui),
     {
+        #[cfg(feature = "docking")]
+        let docking = self.imgui.io().config_flags.contains(imgui::ConfigFlags::DOCKING_ENABLE);
         let ui = self.imgui.frame();
 
-        build_ui(&ui);
+        #[cfg(feature = "docking")]
+        if docking {
+            ui.dockspace_over_main_viewport();
+        }
+        {
+            profile_scope!("build_ui");
+            build_ui(ui);
+        }
 
+        profile_scope!("ui");
         self.platform.prepare_render(ui, window);
         let draw_data = self.imgui.render();
 
-        self
-            .renderer
-            .render(target, draw_data)
-            .expect("imgui rendering failed");
+        self.gpu_timers.collect();
+        let query = self.gpu_timers.begin(&self.context);
+        let drawn = match &query {
+            Some(query) => {
+                let mut timed = TimedSurface::new(target, query);
+                self.renderer.render(&mut timed, draw_data).expect("imgui rendering failed");
+                timed.drawn()
+            }
+            None => {
+                self.renderer.render(target, draw_data).expect("imgui rendering failed");
+                false
+            }
+        };
+        self.gpu_timers.end("ui", query, drawn);
     }
 
     pub fn render_world<F>(&mut self, ecsr: &mut ECSRenderer, window: &Window, build_ui: F)
@@ -86,14 +146,64 @@ impl Gui {
             build_ui(ui, ecsr);
         });
 
+        profile_scope!("present");
         target.finish().expect("Failed to swap buffers");
     }
 
+    /// Draw the world into an offscreen texture `size` logical pixels large
+    /// and build the UI, which gets the texture to show it in a panel.
+    pub fn render_world_in_viewport<F>(&mut self, ecsr: &mut ECSRenderer, window: &Window, size: [f32; 2], build_ui: F)
+    where
+        F: FnOnce(&Ui, &mut ECSRenderer, TextureId),
+    {
+        let [sx, sy] = self.imgui.io().display_framebuffer_scale;
+        let size = ((size[0] * sx).max(1.0) as u32, (size[1] * sy).max(1.0) as u32);
+        let target = match self.viewport_target(ecsr, size) {
+            Ok(target) => target,
+            Err(err) => {
+                eprintln!("[ui] viewport texture: {err:#}");
+                return;
+            }
+        };
+        let id = target.id;
+        match SimpleFrameBuffer::with_depth_buffer(ecsr.renderer.display(), &*target.texture, &target.depth) {
+            Ok(mut framebuffer) => ecsr.render_into(&mut framebuffer),
+            Err(err) => eprintln!("[ui] viewport framebuffer: {err}"),
+        }
+
+        let mut target = ecsr.renderer.display().draw();
+        target.clear_color_and_depth((0.1, 0.1, 0.15, 1.0), 1.0);
+        self.render_with(&mut target, window, |ui| {
+            build_ui(ui, ecsr, id);
+        });
+        profile_scope!("present");
+        target.finish().expect("Failed to swap buffers");
+    }
+
+    /// The offscreen target, (re)created at `size` physical pixels.
+    fn viewport_target(&mut self, ecsr: &ECSRenderer, size: (u32, u32)) -> Result<&ViewportTarget> {
+        if self.viewport.as_ref().is_none_or(|t| t.size != size) {
+            let display = ecsr.renderer.display();
+            let texture = Rc::new(Texture2d::empty(display, size.0, size.1)?);
+            let depth = DepthRenderBuffer::new(display, DepthFormat::I24, size.0, size.1)?;
+            let entry = Texture { texture: texture.clone(), sampler: SamplerBehavior::default() };
+            let id = match self.viewport.take() {
+                Some(old) => {
+                    self.renderer.textures().replace(old.id, entry);
+                    old.id
+                }
+                None => self.renderer.textures().insert(entry),
+            };
+            self.viewport = Some(ViewportTarget { id, texture, depth, size });
+        }
+        Ok(self.viewport.as_ref().unwrap())
+    }
+
     pub fn ui<F>(&mut self, build: F)
     where
         F: FnOnce(&Ui),
     {
         let ui = self.imgui.frame();
-        build(&ui);
+        build(ui);
     }
 }
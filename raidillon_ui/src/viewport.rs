@@ -0,0 +1,55 @@
+//! Shows the 3D view inside a resizable ImGui panel.
+//!
+//! With [`ViewportPlugin`] the window runner renders the world into a
+//! texture sized after the panel instead of the whole window. The panel
+//! publishes its screen rectangle as a [`Viewport`] resource, which keeps the
+//! camera aspect ratio in sync and tells gizmos and picking where the view is.
+//! Game input only gets through while the panel is hovered.
+
+use glam::Vec2;
+use hecs::World;
+use imgui::{Condition, Image, StyleVar, TextureId, Ui};
+use raidillon_ecs::{App, Plugin, Resources};
+use raidillon_input::{CursorGrab, InputFocus};
+use raidillon_render::Viewport;
+
+use crate::systems::UiAppExt;
+
+/// Texture the world was rendered into this frame, set by the runner.
+#[derive(Copy, Clone, Debug)]
+pub struct ViewportTexture(pub TextureId);
+
+pub struct ViewportPlugin;
+
+impl Plugin for ViewportPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Viewport>()
+            .init_resource::<InputFocus>()
+            .add_ui_system(viewport_panel);
+    }
+}
+
+fn viewport_panel(ui: &Ui, _world: &mut World, res: &mut Resources) {
+    let texture = res.get::<ViewportTexture>().map(|t| t.0);
+    let mut viewport = res.fetch_mut::<Viewport>();
+    viewport.hovered = false;
+
+    let padding = ui.push_style_var(StyleVar::WindowPadding([0.0, 0.0]));
+    ui.window("Viewport")
+        .position([260.0, 190.0], Condition::FirstUseEver)
+        .size([740.0, 520.0], Condition::FirstUseEver)
+        .build(|| {
+            let size = ui.content_region_avail();
+            viewport.position = Vec2::from(ui.cursor_screen_pos());
+            viewport.size = Vec2::from(size);
+            match texture {
+                // GL textures start at the bottom, flip vertically.
+                Some(id) => Image::new(id, size).uv0([0.0, 1.0]).uv1([1.0, 0.0]).build(ui),
+                None => ui.dummy(size),
+            }
+            viewport.hovered = ui.is_item_hovered();
+        });
+    padding.pop();
+
+    res.fetch_mut::<InputFocus>().focused = viewport.hovered || res.fetch::<CursorGrab>().grabbed;
+}
@@ -0,0 +1,198 @@
+use glam::Vec3;
+use hecs::{Entity, World};
+use raidillon_ecs::{FieldValue, Name, Parent, ReflectRegistry, Transform};
+use raidillon_ui::history::{Despawn, Reparent, SetField, Spawn};
+use raidillon_ui::History;
+
+fn at(x: f32) -> Transform {
+    Transform { translation: Vec3::new(x, 0.0, 0.0), ..Default::default() }
+}
+
+fn x(world: &World, entity: Entity) -> f32 {
+    world.get::<&Transform>(entity).unwrap().translation.x
+}
+
+fn parent(world: &World, entity: Entity) -> Option<Entity> {
+    world.get::<&Parent>(entity).ok().map(|p| p.0)
+}
+
+/// The frame of a drag that moves `entity` from `before` to `after`.
+fn move_x(entity: Entity, before: f32, after: f32) -> SetField {
+    SetField {
+        entity,
+        component: "Transform",
+        field:     "translation",
+        before:    FieldValue::Vec3(Vec3::new(before, 0.0, 0.0)),
+        after:     FieldValue::Vec3(Vec3::new(after, 0.0, 0.0)),
+    }
+}
+
+#[test]
+fn set_field_round_trip() {
+    let registry = ReflectRegistry::default();
+    let mut world = World::new();
+    let entity = world.spawn((at(0.0),));
+    let mut history = History::default();
+
+    history.execute(move_x(entity, 0.0, 2.0), &mut world, &registry);
+    assert_eq!(x(&world, entity), 2.0);
+    assert!(history.undo(&mut world, &registry));
+    assert_eq!(x(&world, entity), 0.0);
+    assert!(history.redo(&mut world, &registry));
+    assert_eq!(x(&world, entity), 2.0);
+    assert!(!history.redo(&mut world, &registry));
+}
+
+#[test]
+fn despawn_round_trip_keeps_handle_and_components() {
+    let registry = ReflectRegistry::default();
+    let mut world = World::new();
+    let root = world.spawn((Name("root".to_owned()), at(0.0)));
+    let child = world.spawn((Name("child".to_owned()), at(1.0), Parent(root)));
+    let mut history = History::default();
+
+    history.execute(Despawn::new(child), &mut world, &registry);
+    assert!(!world.contains(child));
+    history.undo(&mut world, &registry);
+    assert_eq!(world.get::<&Name>(child).unwrap().0, "child");
+    assert_eq!(x(&world, child), 1.0);
+    assert_eq!(parent(&world, child), Some(root));
+    history.redo(&mut world, &registry);
+    assert!(!world.contains(child));
+}
+
+#[test]
+fn despawn_unlinks_children_until_undone() {
+    let registry = ReflectRegistry::default();
+    let mut world = World::new();
+    let root = world.spawn((at(0.0),));
+    let children = [world.spawn((at(0.0), Parent(root))), world.spawn((at(0.0), Parent(root)))];
+    let other = world.spawn((at(0.0),));
+    let unrelated = world.spawn((at(0.0), Parent(other)));
+    let mut history = History::default();
+
+    history.execute(Despawn::new(root), &mut world, &registry);
+    assert!(children.iter().all(|&c| parent(&world, c).is_none()));
+    assert_eq!(parent(&world, unrelated), Some(other));
+
+    history.undo(&mut world, &registry);
+    assert!(children.iter().all(|&c| parent(&world, c) == Some(root)));
+    history.redo(&mut world, &registry);
+    assert!(children.iter().all(|&c| parent(&world, c).is_none()));
+}
+
+#[test]
+fn spawn_round_trip_keeps_edits() {
+    let registry = ReflectRegistry::default();
+    let mut world = World::new();
+    let mut history = History::default();
+
+    let spawn = Spawn::new(&world);
+    let entity = spawn.entity;
+    history.execute(spawn, &mut world, &registry);
+    history.seal();
+    history.execute(move_x(entity, 0.0, 3.0), &mut world, &registry);
+
+    history.undo(&mut world, &registry);
+    history.undo(&mut world, &registry);
+    assert!(!world.contains(entity));
+    history.redo(&mut world, &registry);
+    history.redo(&mut world, &registry);
+    assert_eq!(x(&world, entity), 3.0);
+}
+
+#[test]
+fn reparent_round_trip() {
+    let registry = ReflectRegistry::default();
+    let mut world = World::new();
+    let a = world.spawn((at(0.0),));
+    let b = world.spawn((at(0.0),));
+    let child = world.spawn((at(0.0),));
+    let mut history = History::default();
+
+    history.execute(Reparent::new(&world, child, Some(a)), &mut world, &registry);
+    history.execute(Reparent::new(&world, child, Some(b)), &mut world, &registry);
+    history.execute(Reparent::new(&world, child, None), &mut world, &registry);
+    assert_eq!(parent(&world, child), None);
+
+    history.undo(&mut world, &registry);
+    assert_eq!(parent(&world, child), Some(b));
+    history.undo(&mut world, &registry);
+    assert_eq!(parent(&world, child), Some(a));
+    history.undo(&mut world, &registry);
+    assert_eq!(parent(&world, child), None);
+    history.redo(&mut world, &registry);
+    assert_eq!(parent(&world, child), Some(a));
+}
+
+#[test]
+fn drag_merges_into_one_step() {
+    let registry = ReflectRegistry::default();
+    let mut world = World::new();
+    let entity = world.spawn((at(0.0),));
+    let mut history = History::default();
+
+    for frame in 0..5 {
+        let frame = frame as f32;
+        history.execute(move_x(entity, frame, frame + 1.0), &mut world, &registry);
+    }
+    assert_eq!(x(&world, entity), 5.0);
+
+    assert!(history.undo(&mut world, &registry));
+    assert_eq!(x(&world, entity), 0.0);
+    assert!(!history.can_undo());
+    history.redo(&mut world, &registry);
+    assert_eq!(x(&world, entity), 5.0);
+}
+
+#[test]
+fn other_field_is_not_merged() {
+    let registry = ReflectRegistry::default();
+    let mut world = World::new();
+    let a = world.spawn((at(0.0),));
+    let b = world.spawn((at(0.0),));
+    let mut history = History::default();
+
+    history.execute(move_x(a, 0.0, 1.0), &mut world, &registry);
+    history.execute(move_x(b, 0.0, 1.0), &mut world, &registry);
+    history.undo(&mut world, &registry);
+    assert_eq!((x(&world, a), x(&world, b)), (1.0, 0.0));
+}
+
+#[test]
+fn seal_ends_the_drag() {
+    let registry = ReflectRegistry::default();
+    let mut world = World::new();
+    let entity = world.spawn((at(0.0),));
+    let mut history = History::default();
+
+    history.execute(move_x(entity, 0.0, 1.0), &mut world, &registry);
+    history.execute(move_x(entity, 1.0, 2.0), &mut world, &registry);
+    history.seal();
+    history.execute(move_x(entity, 2.0, 3.0), &mut world, &registry);
+
+    history.undo(&mut world, &registry);
+    assert_eq!(x(&world, entity), 2.0);
+    history.undo(&mut world, &registry);
+    assert_eq!(x(&world, entity), 0.0);
+}
+
+#[test]
+fn undo_ends_the_drag_and_new_edits_drop_redo() {
+    let registry = ReflectRegistry::default();
+    let mut world = World::new();
+    let entity = world.spawn((at(0.0),));
+    let mut history = History::default();
+
+    history.execute(move_x(entity, 0.0, 1.0), &mut world, &registry);
+    history.execute(move_x(entity, 1.0, 2.0), &mut world, &registry);
+    history.seal();
+    history.execute(move_x(entity, 2.0, 3.0), &mut world, &registry);
+    history.undo(&mut world, &registry);
+
+    // Not merged into the drag that was just undone past.
+    history.execute(move_x(entity, 2.0, 4.0), &mut world, &registry);
+    assert!(!history.can_redo());
+    history.undo(&mut world, &registry);
+    assert_eq!(x(&world, entity), 2.0);
+}
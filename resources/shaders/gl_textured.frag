@@ -7,6 +7,7 @@ in  vec3 v_position;
 out vec4 frag_color;
 
 uniform vec3 u_light;
+uniform vec3 u_light_color;
 uniform sampler2D tex;
 uniform vec3 color; // base colour factor (acts as solid colour when no texture)
 
@@ -25,7 +26,7 @@ void main() {
     vec3 half_dir   = normalize(normalize(u_light) + camera_dir);
     float specular  = pow(max(dot(half_dir, normalize(v_normal)), 0.0), 16.0);
 
-    vec3 result = ambient_color + diffuse * diffuse_color + specular * specular_color;
+    vec3 result = ambient_color + (diffuse * diffuse_color + specular * specular_color) * u_light_color;
 
     // Convert from linear to sRGB for display (approximate γ-correction)
     result = pow(result, vec3(1.0 / 2.2));